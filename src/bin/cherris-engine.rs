@@ -0,0 +1,7 @@
+use std::io::{self, BufReader};
+
+// Runs cherris as a UCI or XBoard engine, to be loaded into a chess GUI
+fn main() -> io::Result<()> {
+    let input = BufReader::new(io::stdin());
+    cherris::cherris_engine::run(input, &mut io::stdout())
+}
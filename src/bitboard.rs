@@ -0,0 +1,407 @@
+use crate::{
+    position_helper, BasicPiece, Move, Piece, PieceType, BISHOP_DIRECTIONS, CASTLE_BLACK_KINGSIDE,
+    CASTLE_BLACK_QUEENSIDE, CASTLE_WHITE_KINGSIDE, CASTLE_WHITE_QUEENSIDE, KING_DIRECTIONS,
+    KNIGHT_DIRECTIONS, ROOK_DIRECTIONS,
+};
+
+/*
+One u64 per piece kind and colour, plus the squares taken by each colour and by any piece.
+Bit i stands for the square with index i in Board::state, so bit 0 is a8 and bit 63 is h1.
+The boards are derived from Board::state and kept up to date by Board::set_piece and
+remove_piece.
+*/
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Bitboards {
+    pieces: [[u64; 6]; 2], // [colour][kind], colour 0 is black
+    colours: [u64; 2],
+    occupied: u64,
+}
+
+const PAWN: usize = 0;
+const KNIGHT: usize = 1;
+const BISHOP: usize = 2;
+const ROOK: usize = 3;
+const QUEEN: usize = 4;
+const KING: usize = 5;
+
+const PROMOTIONS: [PieceType; 4] = [
+    PieceType::Queen,
+    PieceType::Rook,
+    PieceType::Bishop,
+    PieceType::Knight,
+];
+
+// Rows 0 and 7, where pawns promote
+const LAST_ROWS: u64 = 0xff | 0xff << 56;
+
+const KNIGHT_ATTACKS: [u64; 64] = leaper_attacks(&KNIGHT_DIRECTIONS);
+const KING_ATTACKS: [u64; 64] = leaper_attacks(&KING_DIRECTIONS);
+// Squares attacked by a pawn of each colour, white pawns going towards row 0
+const PAWN_ATTACKS: [[u64; 64]; 2] = [
+    leaper_attacks(&[(1, -1), (1, 1)]),
+    leaper_attacks(&[(-1, -1), (-1, 1)]),
+];
+// Every square reached from a square in a direction on an empty board, in the order of
+// ROOK_DIRECTIONS followed by BISHOP_DIRECTIONS
+const RAYS: [[u64; 64]; 8] = [
+    ray_attacks(ROOK_DIRECTIONS[0]),
+    ray_attacks(ROOK_DIRECTIONS[1]),
+    ray_attacks(ROOK_DIRECTIONS[2]),
+    ray_attacks(ROOK_DIRECTIONS[3]),
+    ray_attacks(BISHOP_DIRECTIONS[0]),
+    ray_attacks(BISHOP_DIRECTIONS[1]),
+    ray_attacks(BISHOP_DIRECTIONS[2]),
+    ray_attacks(BISHOP_DIRECTIONS[3]),
+];
+
+// The tables are built by walking position bytes, relying on the 0x88 test to stop at the edges
+const fn leaper_attacks(directions: &[(i8, i8)]) -> [u64; 64] {
+    let mut table = [0u64; 64];
+    let mut index = 0;
+    while index < 64 {
+        let position = square_byte(index);
+        let mut i = 0;
+        while i < directions.len() {
+            let target =
+                position.wrapping_add(position_helper::step(directions[i].0, directions[i].1));
+            if position_helper::is_on_board(target) {
+                table[index] |= 1 << square_index(target);
+            }
+            i += 1;
+        }
+        index += 1;
+    }
+    table
+}
+
+const fn ray_attacks(direction: (i8, i8)) -> [u64; 64] {
+    let mut table = [0u64; 64];
+    let step = position_helper::step(direction.0, direction.1);
+    let mut index = 0;
+    while index < 64 {
+        let mut target = square_byte(index).wrapping_add(step);
+        while position_helper::is_on_board(target) {
+            table[index] |= 1 << square_index(target);
+            target = target.wrapping_add(step);
+        }
+        index += 1;
+    }
+    table
+}
+
+// position_helper::index_to_position_byte and position_byte_to_index, usable in const tables
+const fn square_byte(index: usize) -> u8 {
+    (((index / 8) << 4) | (index % 8)) as u8
+}
+
+const fn square_index(position: u8) -> usize {
+    (position >> 4) as usize * 8 + (position & 0x0f) as usize
+}
+
+// Squares reached along one ray, up to and including the first piece met
+fn ray_until_blocked(ray: usize, index: usize, occupied: u64) -> u64 {
+    let attacks = RAYS[ray][index];
+    let blockers = attacks & occupied;
+    if blockers == 0 {
+        return attacks;
+    }
+    // Rays going down the board meet the lowest blocker first, the others the highest
+    let (d_row, d_col) = if ray < 4 {
+        ROOK_DIRECTIONS[ray]
+    } else {
+        BISHOP_DIRECTIONS[ray - 4]
+    };
+    let first = if d_row * 8 + d_col > 0 {
+        blockers.trailing_zeros()
+    } else {
+        63 - blockers.leading_zeros()
+    } as usize;
+    attacks ^ RAYS[ray][first]
+}
+
+fn rook_attacks(index: usize, occupied: u64) -> u64 {
+    (0..4).fold(0, |attacks, ray| {
+        attacks | ray_until_blocked(ray, index, occupied)
+    })
+}
+
+fn bishop_attacks(index: usize, occupied: u64) -> u64 {
+    (4..8).fold(0, |attacks, ray| {
+        attacks | ray_until_blocked(ray, index, occupied)
+    })
+}
+
+fn kind_index(class: PieceType) -> usize {
+    match class {
+        PieceType::Pawn => PAWN,
+        PieceType::Knight => KNIGHT,
+        PieceType::Bishop => BISHOP,
+        PieceType::Rook => ROOK,
+        PieceType::Queen => QUEEN,
+        PieceType::King => KING,
+    }
+}
+
+// Index of each set bit, from the lowest
+pub(crate) fn squares(mut bitboard: u64) -> impl Iterator<Item = usize> {
+    std::iter::from_fn(move || {
+        if bitboard == 0 {
+            return None;
+        }
+        let index = bitboard.trailing_zeros() as usize;
+        bitboard &= bitboard - 1;
+        Some(index)
+    })
+}
+
+impl Bitboards {
+    pub fn from_state(state: &[u8; 64]) -> Bitboards {
+        let mut bitboards = Bitboards::default();
+        for (index, &binary) in state.iter().enumerate() {
+            if binary != 0 {
+                bitboards.toggle_piece(binary, index);
+            }
+        }
+        bitboards
+    }
+
+    // Adds the piece byte to the square if it was not there, removes it otherwise
+    pub(crate) fn toggle_piece(&mut self, binary: u8, index: usize) {
+        let piece = Piece::init_from_binary(binary);
+        self.toggle(piece.is_white, kind_index(piece.class), index);
+    }
+
+    pub fn pieces(&self, is_white: bool, class: PieceType) -> u64 {
+        self.pieces[is_white as usize][kind_index(class)]
+    }
+
+    pub fn colour(&self, is_white: bool) -> u64 {
+        self.colours[is_white as usize]
+    }
+
+    pub fn occupied(&self) -> u64 {
+        self.occupied
+    }
+
+    fn toggle(&mut self, is_white: bool, kind: usize, index: usize) {
+        let bit = 1u64 << index;
+        self.pieces[is_white as usize][kind] ^= bit;
+        self.colours[is_white as usize] ^= bit;
+        self.occupied ^= bit;
+    }
+
+    fn kind_at(&self, is_white: bool, index: usize) -> Option<usize> {
+        let bit = 1u64 << index;
+        (PAWN..=KING).find(|&kind| self.pieces[is_white as usize][kind] & bit != 0)
+    }
+
+    pub fn king_index(&self, is_white: bool) -> Option<usize> {
+        let kings = self.pieces[is_white as usize][KING];
+        (kings != 0).then(|| kings.trailing_zeros() as usize)
+    }
+
+    // Whether a pawn of the given colour attacks the square
+    pub(crate) fn pawn_attacks(&self, is_white: bool, index: usize) -> bool {
+        PAWN_ATTACKS[!is_white as usize][index] & self.pieces[is_white as usize][PAWN] != 0
+    }
+
+    pub fn is_attacked(&self, index: usize, by_white: bool) -> bool {
+        let attackers = &self.pieces[by_white as usize];
+        // A pawn attacks this square if a pawn of the other colour here would attack it
+        PAWN_ATTACKS[!by_white as usize][index] & attackers[PAWN] != 0
+            || KNIGHT_ATTACKS[index] & attackers[KNIGHT] != 0
+            || KING_ATTACKS[index] & attackers[KING] != 0
+            || bishop_attacks(index, self.occupied) & (attackers[BISHOP] | attackers[QUEEN]) != 0
+            || rook_attacks(index, self.occupied) & (attackers[ROOK] | attackers[QUEEN]) != 0
+    }
+
+    // Plays a move on the boards, the counterpart of Board::make_move
+    fn play(&mut self, chess_move: Move, is_white: bool) {
+        let initial = position_helper::position_byte_to_index(chess_move.initial_position());
+        let final_index = position_helper::position_byte_to_index(chess_move.final_position());
+        let kind = self
+            .kind_at(is_white, initial)
+            .expect("a piece of the side to move is on the initial square");
+
+        if chess_move.is_en_passant() {
+            let captured = initial / 8 * 8 + final_index % 8;
+            self.toggle(!is_white, PAWN, captured);
+        } else if let Some(taken) = self.kind_at(!is_white, final_index) {
+            self.toggle(!is_white, taken, final_index);
+        }
+
+        self.toggle(is_white, kind, initial);
+        let new_kind = chess_move.promotion().map_or(kind, kind_index);
+        self.toggle(is_white, new_kind, final_index);
+
+        if chess_move.is_castle() {
+            let row = initial / 8 * 8;
+            let (rook_from, rook_to) = if chess_move.flags() == Move::KING_CASTLE {
+                (row + 7, row + 5)
+            } else {
+                (row, row + 3)
+            };
+            self.toggle(is_white, ROOK, rook_from);
+            self.toggle(is_white, ROOK, rook_to);
+        }
+    }
+
+    // Every legal move of the side to move. Each pseudo-legal move is played on a copy of the
+    // boards, which is cheap as they are only a few integers, to see if it leaves the king attacked.
+    pub(crate) fn legal_moves(
+        &self,
+        white_turn: bool,
+        castling_rights: u8,
+        en_passant: Option<u8>,
+    ) -> Vec<Move> {
+        let mut moves = self.pseudo_legal_moves(white_turn, castling_rights, en_passant);
+        moves.retain(|&chess_move| {
+            let mut bitboards = *self;
+            bitboards.play(chess_move, white_turn);
+            bitboards
+                .king_index(white_turn)
+                .is_none_or(|king| !bitboards.is_attacked(king, !white_turn))
+        });
+        moves
+    }
+
+    fn pseudo_legal_moves(
+        &self,
+        white_turn: bool,
+        castling_rights: u8,
+        en_passant: Option<u8>,
+    ) -> Vec<Move> {
+        let mut moves = Vec::new();
+        let own = &self.pieces[white_turn as usize];
+        let opponent = self.colours[!white_turn as usize];
+        let targets = !self.colours[white_turn as usize];
+
+        for from in squares(own[PAWN]) {
+            self.pawn_moves(from, white_turn, opponent, en_passant, &mut moves);
+        }
+        for from in squares(own[KNIGHT]) {
+            push_moves(from, KNIGHT_ATTACKS[from] & targets, opponent, &mut moves);
+        }
+        for from in squares(own[BISHOP] | own[QUEEN]) {
+            let attacks = bishop_attacks(from, self.occupied);
+            push_moves(from, attacks & targets, opponent, &mut moves);
+        }
+        for from in squares(own[ROOK] | own[QUEEN]) {
+            let attacks = rook_attacks(from, self.occupied);
+            push_moves(from, attacks & targets, opponent, &mut moves);
+        }
+        for from in squares(own[KING]) {
+            push_moves(from, KING_ATTACKS[from] & targets, opponent, &mut moves);
+        }
+        self.castling_moves(white_turn, castling_rights, &mut moves);
+
+        moves
+    }
+
+    fn pawn_moves(
+        &self,
+        from: usize,
+        white_turn: bool,
+        opponent: u64,
+        en_passant: Option<u8>,
+        moves: &mut Vec<Move>,
+    ) {
+        let (start_row, forward) = if white_turn { (6, -8) } else { (1, 8) };
+        let mut destinations = PAWN_ATTACKS[white_turn as usize][from] & opponent;
+
+        let one_step = (from as i32 + forward) as usize;
+        if self.occupied & (1 << one_step) == 0 {
+            destinations |= 1 << one_step;
+            let two_steps = (one_step as i32 + forward) as usize;
+            if from / 8 == start_row && self.occupied & (1 << two_steps) == 0 {
+                moves.push(index_move(from, two_steps, Move::DOUBLE_PAWN_PUSH));
+            }
+        }
+
+        for to in squares(destinations) {
+            let is_capture = opponent & (1 << to) != 0;
+            if (1u64 << to) & LAST_ROWS != 0 {
+                let (initial, final_position) = (
+                    position_helper::index_to_position_byte(from),
+                    position_helper::index_to_position_byte(to),
+                );
+                moves.extend(PROMOTIONS.iter().filter_map(|&promotion| {
+                    Move::new_promotion(initial, final_position, promotion, is_capture)
+                }));
+            } else {
+                let flags = if is_capture {
+                    Move::CAPTURE
+                } else {
+                    Move::QUIET
+                };
+                moves.push(index_move(from, to, flags));
+            }
+        }
+
+        if let Some(target) = en_passant {
+            let to = position_helper::position_byte_to_index(target);
+            // The pawn that made the double push stands in front of the target square
+            let captured = if white_turn { to + 8 } else { to - 8 };
+            let opponent_pawns = self.pieces[!white_turn as usize][PAWN];
+            if PAWN_ATTACKS[white_turn as usize][from] & (1 << to) != 0
+                && opponent_pawns & (1 << captured) != 0
+            {
+                moves.push(index_move(from, to, Move::EN_PASSANT));
+            }
+        }
+    }
+
+    // Castling is not allowed out of, through or into check, and every square between the king
+    // and the rook must be empty
+    fn castling_moves(&self, white_turn: bool, castling_rights: u8, moves: &mut Vec<Move>) {
+        let (row, kingside, queenside) = if white_turn {
+            (56, CASTLE_WHITE_KINGSIDE, CASTLE_WHITE_QUEENSIDE)
+        } else {
+            (0, CASTLE_BLACK_KINGSIDE, CASTLE_BLACK_QUEENSIDE)
+        };
+        let own = &self.pieces[white_turn as usize];
+        let king = row + 4;
+        if castling_rights & (kingside | queenside) == 0
+            || own[KING] & (1 << king) == 0
+            || self.is_attacked(king, !white_turn)
+        {
+            return;
+        }
+
+        let has_rook = |col: usize| own[ROOK] & (1 << (row + col)) != 0;
+        let empty = |cols: &[usize]| {
+            cols.iter()
+                .all(|col| self.occupied & (1 << (row + col)) == 0)
+        };
+        let safe = |cols: &[usize]| {
+            cols.iter()
+                .all(|col| !self.is_attacked(row + col, !white_turn))
+        };
+
+        if castling_rights & kingside != 0 && has_rook(7) && empty(&[5, 6]) && safe(&[5, 6]) {
+            moves.push(index_move(king, row + 6, Move::KING_CASTLE));
+        }
+        if castling_rights & queenside != 0 && has_rook(0) && empty(&[1, 2, 3]) && safe(&[2, 3]) {
+            moves.push(index_move(king, row + 2, Move::QUEEN_CASTLE));
+        }
+    }
+}
+
+fn index_move(from: usize, to: usize, flags: u8) -> Move {
+    Move::new(
+        position_helper::index_to_position_byte(from),
+        position_helper::index_to_position_byte(to),
+        flags,
+    )
+}
+
+fn push_moves(from: usize, destinations: u64, opponent: u64, moves: &mut Vec<Move>) {
+    for to in squares(destinations) {
+        let flags = if opponent & (1 << to) != 0 {
+            Move::CAPTURE
+        } else {
+            Move::QUIET
+        };
+        moves.push(index_move(from, to, flags));
+    }
+}
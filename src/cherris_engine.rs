@@ -0,0 +1,276 @@
+use std::{
+    fmt,
+    io::{self, BufRead, Cursor, Read, Write},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    thread,
+    time::{Duration, Instant},
+};
+
+use crate::{ChessGame, FenError, Game, Move, UciMoveError};
+
+mod protocol;
+mod search;
+mod time_manager;
+mod transposition;
+pub mod uci;
+pub mod xboard;
+
+use search::Searcher;
+pub use search::MATE_SCORE;
+use time_manager::TimeBudget;
+use transposition::TranspositionTable;
+
+// Depth searched when the GUI gives neither a depth nor any time
+const DEFAULT_DEPTH: u32 = 4;
+const MAX_DEPTH: u32 = 64;
+
+// Size of the transposition table in megabytes, as set with the Hash option
+const DEFAULT_HASH_SIZE: usize = 16;
+const MAX_HASH_SIZE: usize = 4096;
+
+/*
+The engine keeps the game being analysed and searches it when asked by one of the protocol
+front ends. Searches look at the stop flag, which the thread handling the commands sets when
+told to stop, so that they can end while the search thread is busy.
+*/
+pub struct Engine {
+    game: Game,
+    table: TranspositionTable,
+    stop: Arc<AtomicBool>,
+}
+
+// What the GUI allows for one search, as given by the UCI go command
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SearchLimits {
+    pub depth: Option<u32>,
+    pub movetime: Option<Duration>,
+    pub wtime: Option<Duration>,
+    pub btime: Option<Duration>,
+    pub winc: Option<Duration>,
+    pub binc: Option<Duration>,
+    pub movestogo: Option<u32>,
+    pub infinite: bool,
+}
+
+// The move found by a search, None when there is no legal move, and its score in centipawns
+// from the point of view of the side to move, with the last depth that was searched in full
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SearchResult {
+    pub best_move: Option<Move>,
+    pub score: i32,
+    pub depth: u32,
+    pub nodes: u64,
+}
+
+// What the search knows once it is done with a depth, for the GUI to show
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SearchInfo {
+    pub depth: u32,
+    pub score: i32,
+    pub nodes: u64,
+    pub time: Duration,
+    pub principal_variation: Vec<Move>,
+}
+
+/// Error returned when a position sent by the GUI cannot be set up.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PositionError {
+    Fen(FenError),
+    Move(UciMoveError),
+}
+
+impl fmt::Display for PositionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PositionError::Fen(error) => write!(f, "{}", error),
+            PositionError::Move(error) => write!(f, "{}", error),
+        }
+    }
+}
+
+impl std::error::Error for PositionError {}
+
+impl Engine {
+    pub fn new() -> Engine {
+        Engine {
+            game: Game::init(),
+            table: TranspositionTable::new(DEFAULT_HASH_SIZE),
+            stop: Arc::new(AtomicBool::new(false)),
+        }
+    }
+
+    pub fn game(&self) -> &Game {
+        &self.game
+    }
+
+    // Starts over, forgetting what was learnt about the previous game
+    pub fn new_game(&mut self) {
+        self.game = Game::init();
+        self.table.clear();
+    }
+
+    pub fn set_game(&mut self, game: Game) {
+        self.game = game;
+    }
+
+    // Sets up the position from a FEN and the coordinate moves played from it. The previous
+    // position is kept when anything is wrong.
+    pub fn set_position(&mut self, fen: &str, moves: &[&str]) -> Result<(), PositionError> {
+        let mut game = Game::from_fen(fen).map_err(PositionError::Fen)?;
+        for uci in moves {
+            let chess_move = Move::from_uci(&game, uci).map_err(PositionError::Move)?;
+            // GUIs may go on after a draw the game could have stopped at, so the end of the
+            // game is not checked here
+            game.apply_move(chess_move);
+        }
+        game.update_outcome();
+        self.game = game;
+        Ok(())
+    }
+
+    // Plays a move that must be legal in the current position
+    pub fn play_move(&mut self, chess_move: Move) {
+        self.game.apply_move(chess_move);
+        self.game.update_outcome();
+    }
+
+    pub fn undo_move(&mut self) -> Option<Move> {
+        self.game.undo_move()
+    }
+
+    // Options sent by the GUI with setoption. Names are not case sensitive.
+    pub fn set_option(&mut self, name: &str, value: &str) -> Result<(), String> {
+        if name.eq_ignore_ascii_case("Hash") {
+            let size = value
+                .parse::<usize>()
+                .ok()
+                .filter(|size| (1..=MAX_HASH_SIZE).contains(size))
+                .ok_or_else(|| format!("invalid Hash size {}", value))?;
+            self.table = TranspositionTable::new(size);
+            Ok(())
+        } else {
+            Err(format!("unknown option {}", name))
+        }
+    }
+
+    // The flag a search ends on. Whoever sets it is in charge of clearing it once the stop
+    // has been dealt with.
+    pub fn stop_flag(&self) -> Arc<AtomicBool> {
+        Arc::clone(&self.stop)
+    }
+
+    fn is_stopped(&self) -> bool {
+        self.stop.load(Ordering::Relaxed)
+    }
+
+    pub fn search(&mut self, limits: &SearchLimits) -> SearchResult {
+        self.search_with_info(limits, |_| {})
+    }
+
+    /*
+    Iterative deepening: the position is searched to depth 1, then 2 and so on, until the depth
+    of the limits, the time they allow or the stop flag. Each depth starts with the best moves
+    of the previous one thanks to the transposition table, so the repeated work costs little
+    and there is always a move to play when the time runs out. Every finished depth is given to
+    report.
+    */
+    pub fn search_with_info<F>(&mut self, limits: &SearchLimits, mut report: F) -> SearchResult
+    where
+        F: FnMut(&SearchInfo),
+    {
+        let start = Instant::now();
+        let budget = TimeBudget::new(limits, self.game.white_turn());
+        let max_depth = match limits.depth {
+            Some(depth) => depth.clamp(1, MAX_DEPTH),
+            None if limits.infinite || budget.hard.is_some() => MAX_DEPTH,
+            None => DEFAULT_DEPTH,
+        };
+
+        self.table.new_search();
+        let outcome = self.game.outcome.take();
+        let deadline = budget.hard.map(|hard| start + hard);
+        let mut searcher = Searcher::new(&mut self.game, &mut self.table, &self.stop, deadline);
+        let mut result = SearchResult {
+            best_move: None,
+            score: 0,
+            depth: 0,
+            nodes: 0,
+        };
+        for depth in 1..=max_depth {
+            // A depth cut short still gives a move at least as good as the previous one, as
+            // the best move of the previous depth is searched first
+            let Some((best_move, score)) = searcher.search_root(depth) else {
+                break;
+            };
+            result.best_move = best_move;
+            result.score = score;
+            if searcher.is_stopped() {
+                break;
+            }
+            result.depth = depth;
+            // Mated or stalemated, there is nothing to search
+            if best_move.is_none() {
+                break;
+            }
+            report(&SearchInfo {
+                depth,
+                score,
+                nodes: searcher.nodes,
+                time: start.elapsed(),
+                principal_variation: searcher.principal_variation(depth),
+            });
+
+            // The next depth usually takes longer than all the previous ones together, it is not
+            // started when it would most likely end past the soft limit
+            if budget.soft.is_some_and(|soft| start.elapsed() * 2 >= soft) {
+                break;
+            }
+        }
+        result.nodes = searcher.nodes;
+        self.game.outcome = outcome;
+
+        // An infinite search only ends when told to
+        while limits.infinite && !self.is_stopped() {
+            thread::sleep(Duration::from_millis(1));
+        }
+        result
+    }
+}
+
+impl Default for Engine {
+    fn default() -> Self {
+        Engine::new()
+    }
+}
+
+/*
+Speaks the protocol the GUI asks for: XBoard (CECP) when its first command is "xboard", UCI
+otherwise. The first command is then handled by that protocol like any other.
+*/
+pub fn run<R, W>(mut input: R, output: &mut W) -> io::Result<()>
+where
+    R: BufRead + Send + 'static,
+    W: Write,
+{
+    let mut first_line = String::new();
+    while first_line.trim().is_empty() {
+        first_line.clear();
+        if input.read_line(&mut first_line)? == 0 {
+            return Ok(());
+        }
+    }
+    if !first_line.ends_with('\n') {
+        first_line.push('\n');
+    }
+
+    let is_xboard = first_line.split_whitespace().next() == Some("xboard");
+    let input = Cursor::new(first_line).chain(input);
+    if is_xboard {
+        xboard::run(input, output)
+    } else {
+        uci::run(input, output)
+    }
+}
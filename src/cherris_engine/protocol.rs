@@ -0,0 +1,165 @@
+use std::{
+    collections::VecDeque,
+    io::{self, BufRead, Write},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        mpsc,
+    },
+    thread,
+};
+
+use super::{Engine, SearchInfo, SearchLimits, SearchResult};
+
+// What a protocol does with the commands of the GUI and the searches they start
+pub(crate) trait Protocol {
+    // Handles a command while no search runs
+    fn handle_command<W: Write>(
+        &mut self,
+        engine: &mut Engine,
+        line: &str,
+        output: &mut W,
+    ) -> io::Result<Next>;
+
+    // Handles a command that came during a search, the search ends when stop is set.
+    // Returns false when the command has to wait for the search to be over.
+    fn command_during_search<W: Write>(
+        &mut self,
+        line: &str,
+        stop: &AtomicBool,
+        output: &mut W,
+    ) -> io::Result<bool>;
+
+    fn write_info<W: Write>(&mut self, info: &SearchInfo, output: &mut W) -> io::Result<()>;
+
+    fn search_done<W: Write>(
+        &mut self,
+        engine: &mut Engine,
+        result: SearchResult,
+        output: &mut W,
+    ) -> io::Result<()>;
+}
+
+// What the loop does once a command is handled
+pub(crate) enum Next {
+    Continue,
+    Search(SearchLimits),
+    Quit,
+}
+
+/*
+The loop shared by the protocols. Commands come one per line on the input and searches run on a
+thread of their own, so that the protocol can deal with some commands while the engine thinks.
+The other commands wait for the search to end, and so does a quit, unless the search would never
+end by itself. The end of the input counts as quit.
+*/
+pub(crate) fn run<R, W, P>(input: R, output: &mut W, protocol: &mut P) -> io::Result<()>
+where
+    R: BufRead + Send + 'static,
+    W: Write,
+    P: Protocol,
+{
+    let (sender, receiver) = mpsc::channel();
+    spawn_reader(input, sender.clone());
+    let engine = Engine::new();
+    let stop = engine.stop_flag();
+    // Taken by the search thread while it runs
+    let mut engine = Some(engine);
+    // Commands that came during a search, in order
+    let mut pending = VecDeque::new();
+    let mut infinite = false;
+    let mut input_over = false;
+
+    loop {
+        let queued = if engine.is_some() {
+            pending.pop_front().map(Event::Command)
+        } else {
+            None
+        };
+        let Some(event) = queued.or_else(|| receiver.recv().ok()) else {
+            break;
+        };
+        match event {
+            Event::Info(info) => {
+                protocol.write_info(&info, output)?;
+                output.flush()?;
+            }
+            Event::Done(mut searched, result) => {
+                stop.store(false, Ordering::Relaxed);
+                protocol.search_done(&mut searched, result, output)?;
+                output.flush()?;
+                engine = Some(*searched);
+            }
+            // Counts as quit, a running search still ends as it would have unless it never would
+            Event::EndOfInput => {
+                input_over = true;
+                if engine.is_none() && infinite {
+                    stop.store(true, Ordering::Relaxed);
+                }
+                pending.push_back(String::from("quit"));
+            }
+            Event::Command(line) => {
+                let Some(mut idle) = engine.take() else {
+                    // Waiting for the end of an infinite search would be forever
+                    if infinite && line.split_whitespace().next() == Some("quit") {
+                        stop.store(true, Ordering::Relaxed);
+                    }
+                    if !protocol.command_during_search(&line, &stop, output)? {
+                        pending.push_back(line);
+                    }
+                    output.flush()?;
+                    continue;
+                };
+                match protocol.handle_command(&mut idle, &line, output)? {
+                    Next::Continue => engine = Some(idle),
+                    Next::Search(limits) => {
+                        infinite = limits.infinite;
+                        spawn_search(idle, limits, sender.clone());
+                        if infinite && input_over {
+                            stop.store(true, Ordering::Relaxed);
+                        }
+                    }
+                    Next::Quit => break,
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+// What the thread handling the commands waits for
+enum Event {
+    Command(String),
+    EndOfInput,
+    Info(SearchInfo),
+    // The engine comes back once its search is over
+    Done(Box<Engine>, SearchResult),
+}
+
+// Sends every line of the input to the channel, then tells when the input is over
+fn spawn_reader<R>(input: R, sender: mpsc::Sender<Event>)
+where
+    R: BufRead + Send + 'static,
+{
+    thread::spawn(move || {
+        for line in input.lines() {
+            let Ok(line) = line else {
+                break;
+            };
+            if sender.send(Event::Command(line)).is_err() {
+                return;
+            }
+        }
+        let _ = sender.send(Event::EndOfInput);
+    });
+}
+
+// Searches on another thread, sending every finished depth and then the engine back with the
+// result. The search ends on the stop flag of the engine like any other.
+fn spawn_search(mut engine: Engine, limits: SearchLimits, sender: mpsc::Sender<Event>) {
+    thread::spawn(move || {
+        let result = engine.search_with_info(&limits, |info| {
+            let _ = sender.send(Event::Info(info.clone()));
+        });
+        let _ = sender.send(Event::Done(Box::new(engine), result));
+    });
+}
@@ -0,0 +1,303 @@
+use std::{
+    sync::atomic::{AtomicBool, Ordering},
+    time::Instant,
+};
+
+use super::transposition::{Bound, TranspositionTable};
+use crate::{BasicPiece, ChessGame, Game, Move, Piece, PieceType};
+
+// Score of being mated right now. Being mated in n plies scores -(MATE_SCORE - n), so that the
+// search prefers the quickest mates and the longest defences.
+pub const MATE_SCORE: i32 = 30_000;
+// Scores further from zero than this are mates, MATE_SCORE minus the plies to the mate
+pub(super) const MATE_BOUND: i32 = MATE_SCORE - 1000;
+const INFINITY: i32 = 32_000;
+
+// Used to put the captures that win the most first
+const PIECE_VALUES: [(PieceType, i32); 5] = [
+    (PieceType::Pawn, 100),
+    (PieceType::Knight, 320),
+    (PieceType::Bishop, 330),
+    (PieceType::Rook, 500),
+    (PieceType::Queen, 900),
+];
+
+/*
+Negamax with alpha-beta pruning: every score is from the point of view of the side to move, so
+the score of a move is minus the score of the position it leads to from the other side. Leaves
+are resolved with a quiescence search that only plays captures and promotions, so that the
+material is not counted in the middle of an exchange. The results go to the transposition
+table, which cuts the search short in positions already searched deep enough and otherwise
+tells which move to try first.
+*/
+pub(super) struct Searcher<'a> {
+    game: &'a mut Game,
+    table: &'a mut TranspositionTable,
+    stop: &'a AtomicBool,
+    deadline: Option<Instant>,
+    pub(super) nodes: u64,
+    stopped: bool,
+    // Set while searching depth 1, which is never cut short so that there is a move to play
+    must_finish: bool,
+}
+
+impl<'a> Searcher<'a> {
+    pub(super) fn new(
+        game: &'a mut Game,
+        table: &'a mut TranspositionTable,
+        stop: &'a AtomicBool,
+        deadline: Option<Instant>,
+    ) -> Searcher<'a> {
+        Searcher {
+            game,
+            table,
+            stop,
+            deadline,
+            nodes: 0,
+            stopped: false,
+            must_finish: false,
+        }
+    }
+
+    // Returns the best move with its score, no move when there is no legal move. When the
+    // search is stopped, the best of the moves searched so far is returned, or nothing when
+    // none was. Depth 1 always runs to the end, whatever the clock or the stop flag say. The
+    // end of the game is looked for by the search itself, the outcome of the game must be
+    // cleared before.
+    pub(super) fn search_root(&mut self, depth: u32) -> Option<(Option<Move>, i32)> {
+        self.must_finish = depth == 1;
+        let key = self.game.zobrist_key();
+        let table_move = self.table.probe(key, 0).and_then(|hit| hit.best_move);
+        let moves = self.ordered_moves(false, table_move);
+
+        let mut best_move = moves.first().copied();
+        let mut best_score = if moves.is_empty() {
+            self.no_moves_score(0)
+        } else {
+            -INFINITY
+        };
+        for chess_move in moves {
+            self.game.apply_move(chess_move);
+            let score = -self.negamax(depth - 1, 1, -INFINITY, -best_score);
+            self.game.undo_move();
+            if self.stopped {
+                break;
+            }
+            if score > best_score {
+                best_score = score;
+                best_move = Some(chess_move);
+            }
+        }
+        if best_score == -INFINITY {
+            return None;
+        }
+        if !self.stopped {
+            self.table
+                .store(key, depth, 0, best_score, Bound::Exact, best_move);
+        }
+        Some((best_move, best_score))
+    }
+
+    // The moves both sides are expected to play, as found in the table after a search
+    pub(super) fn principal_variation(&mut self, depth: u32) -> Vec<Move> {
+        let mut variation = Vec::new();
+        while variation.len() < depth as usize {
+            let key = self.game.zobrist_key();
+            let Some(chess_move) = self.table.probe(key, 0).and_then(|hit| hit.best_move) else {
+                break;
+            };
+            // Another position may have taken the slot since
+            if !self.game.legal_moves().contains(&chess_move) {
+                break;
+            }
+            self.game.apply_move(chess_move);
+            variation.push(chess_move);
+            if self.game.repetition_count() >= 2 {
+                break;
+            }
+        }
+        for _ in &variation {
+            self.game.undo_move();
+        }
+        variation
+    }
+
+    pub(super) fn is_stopped(&self) -> bool {
+        self.stopped
+    }
+
+    fn negamax(&mut self, depth: u32, ply: i32, mut alpha: i32, beta: i32) -> i32 {
+        if self.should_stop() {
+            return 0;
+        }
+        if self.is_draw() {
+            return 0;
+        }
+        if depth == 0 {
+            return self.quiescence(alpha, beta);
+        }
+        self.nodes += 1;
+
+        let key = self.game.zobrist_key();
+        let hit = self.table.probe(key, ply);
+        if let Some(hit) = hit.filter(|hit| hit.depth >= depth) {
+            let usable = match hit.bound {
+                Bound::Exact => true,
+                Bound::Lower => hit.score >= beta,
+                Bound::Upper => hit.score <= alpha,
+            };
+            if usable {
+                return hit.score;
+            }
+        }
+
+        let moves = self.ordered_moves(false, hit.and_then(|hit| hit.best_move));
+        if moves.is_empty() {
+            return self.no_moves_score(ply);
+        }
+        let original_alpha = alpha;
+        let mut best_score = -INFINITY;
+        let mut best_move = None;
+        for chess_move in moves {
+            self.game.apply_move(chess_move);
+            let score = -self.negamax(depth - 1, ply + 1, -beta, -alpha);
+            self.game.undo_move();
+            if self.stopped {
+                return 0;
+            }
+            if score > best_score {
+                best_score = score;
+                best_move = Some(chess_move);
+            }
+            alpha = alpha.max(score);
+            if alpha >= beta {
+                break;
+            }
+        }
+
+        let bound = if best_score >= beta {
+            Bound::Lower
+        } else if best_score > original_alpha {
+            Bound::Exact
+        } else {
+            Bound::Upper
+        };
+        // Below alpha every move was refuted, none of them is known to be the best
+        let best_move = if bound == Bound::Upper {
+            None
+        } else {
+            best_move
+        };
+        self.table
+            .store(key, depth, ply, best_score, bound, best_move);
+        best_score
+    }
+
+    fn quiescence(&mut self, mut alpha: i32, beta: i32) -> i32 {
+        if self.should_stop() {
+            return 0;
+        }
+        self.nodes += 1;
+
+        // The side to move does not have to take, so the static score is a lower bound
+        let stand_pat = self.game.board().evaluate(self.game.white_turn());
+        if stand_pat >= beta {
+            return stand_pat;
+        }
+        alpha = alpha.max(stand_pat);
+
+        let mut best_score = stand_pat;
+        for chess_move in self.ordered_moves(true, None) {
+            self.game.apply_move(chess_move);
+            let score = -self.quiescence(-beta, -alpha);
+            self.game.undo_move();
+            if self.stopped {
+                return 0;
+            }
+            best_score = best_score.max(score);
+            alpha = alpha.max(score);
+            if alpha >= beta {
+                break;
+            }
+        }
+        best_score
+    }
+
+    fn should_stop(&mut self) -> bool {
+        if self.must_finish {
+            return false;
+        }
+        if !self.stopped && self.stop.load(Ordering::Relaxed) {
+            self.stopped = true;
+        }
+        // Reading the clock costs more than a node, it is only looked at now and then
+        if !self.stopped && self.nodes.is_multiple_of(1024) {
+            self.stopped = self
+                .deadline
+                .is_some_and(|deadline| Instant::now() >= deadline);
+        }
+        self.stopped
+    }
+
+    // Draws the search can run into: repetitions, the fifty-move rule and dead positions. A
+    // position seen once before already counts, since nothing keeps it from repeating again.
+    fn is_draw(&self) -> bool {
+        self.game.halfmove_clock() >= 100
+            || self.game.repetition_count() >= 2
+            || self.game.has_insufficient_material()
+    }
+
+    fn no_moves_score(&self, ply: i32) -> i32 {
+        if self.game.is_in_check() {
+            -MATE_SCORE + ply
+        } else {
+            0
+        }
+    }
+
+    // The best move of an earlier search of the position first, then captures of the most
+    // valuable pieces and promotions, the cut-offs come sooner
+    fn ordered_moves(&self, only_tactical: bool, table_move: Option<Move>) -> Vec<Move> {
+        let mut moves: Vec<(Move, i32)> = self
+            .game
+            .legal_moves()
+            .into_iter()
+            .map(|chess_move| (chess_move, self.move_gain(chess_move)))
+            .filter(|&(_, gain)| !only_tactical || gain > 0)
+            .collect();
+        moves.sort_by_key(|&(chess_move, gain)| {
+            if Some(chess_move) == table_move {
+                i32::MIN
+            } else {
+                -gain
+            }
+        });
+        moves
+            .into_iter()
+            .map(|(chess_move, _)| chess_move)
+            .collect()
+    }
+
+    // The material a move wins at once, before any answer
+    fn move_gain(&self, chess_move: Move) -> i32 {
+        let mut gain = 0;
+        if chess_move.is_en_passant() {
+            gain += piece_value(PieceType::Pawn);
+        } else if chess_move.is_capture() {
+            if let Some(&binary) = self.game.board().pieces.get(&chess_move.final_position()) {
+                gain += piece_value(Piece::init_from_binary(binary).class);
+            }
+        }
+        if let Some(promotion) = chess_move.promotion() {
+            gain += piece_value(promotion) - piece_value(PieceType::Pawn);
+        }
+        gain
+    }
+}
+
+fn piece_value(class: PieceType) -> i32 {
+    PIECE_VALUES
+        .iter()
+        .find(|(piece, _)| *piece == class)
+        .map_or(0, |(_, value)| *value)
+}
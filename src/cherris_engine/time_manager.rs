@@ -0,0 +1,66 @@
+use std::time::Duration;
+
+use super::SearchLimits;
+
+// Time kept aside for the GUI and the pipes between us, so that the engine does not lose on
+// time when its clock runs low
+const MOVE_OVERHEAD: Duration = Duration::from_millis(30);
+// Even with next to nothing on the clock, the search gets the time to look at every move once
+const MINIMUM_TIME: Duration = Duration::from_millis(10);
+// Moves the time left is spread over when the GUI does not say when the next control is
+const DEFAULT_MOVES_TO_GO: u32 = 30;
+
+/*
+How long a search may take. The soft limit is the time the search should take, new depths are
+only started when they are likely to be done by then. The hard limit ends the search wherever
+it is. Both are missing when the search only ends on its depth or when told to stop.
+*/
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) struct TimeBudget {
+    pub(super) soft: Option<Duration>,
+    pub(super) hard: Option<Duration>,
+}
+
+impl TimeBudget {
+    pub(super) fn new(limits: &SearchLimits, white_turn: bool) -> TimeBudget {
+        let unlimited = TimeBudget {
+            soft: None,
+            hard: None,
+        };
+        if limits.infinite {
+            return unlimited;
+        }
+        if let Some(movetime) = limits.movetime {
+            let time = movetime.saturating_sub(MOVE_OVERHEAD).max(MINIMUM_TIME);
+            return TimeBudget {
+                soft: Some(time),
+                hard: Some(time),
+            };
+        }
+
+        let (time, increment) = if white_turn {
+            (limits.wtime, limits.winc)
+        } else {
+            (limits.btime, limits.binc)
+        };
+        let Some(time) = time else {
+            return unlimited;
+        };
+        let available = time.saturating_sub(MOVE_OVERHEAD).max(MINIMUM_TIME);
+        let moves_to_go = limits
+            .movestogo
+            .unwrap_or(DEFAULT_MOVES_TO_GO)
+            .clamp(1, DEFAULT_MOVES_TO_GO);
+
+        // An even share of the clock, plus most of what the increment gives back
+        let soft = (available / moves_to_go + increment.unwrap_or_default() * 3 / 4)
+            .clamp(MINIMUM_TIME, available);
+        // A search may run over its share when a depth takes long, but never into the time
+        // needed by the next moves
+        let hard = (soft * 4).min(available / 2).max(soft);
+        TimeBudget {
+            soft: Some(soft),
+            hard: Some(hard),
+        }
+    }
+}
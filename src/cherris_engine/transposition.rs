@@ -0,0 +1,143 @@
+use std::mem;
+
+use super::search::MATE_BOUND;
+use crate::Move;
+
+// How the stored score relates to the true score of the position
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) enum Bound {
+    Exact,
+    // The search failed high, the true score is at least this
+    Lower,
+    // No move reached alpha, the true score is at most this
+    Upper,
+}
+
+// What a probe finds, with a mate score already counted from the probing node
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) struct TableHit {
+    pub(super) depth: u32,
+    pub(super) score: i32,
+    pub(super) bound: Bound,
+    pub(super) best_move: Option<Move>,
+}
+
+// 16 bytes, a key of 0 marks an empty slot
+#[derive(Debug, Clone, Copy, Default)]
+struct Entry {
+    key: u64,
+    best_move: u16,
+    score: i16,
+    depth: u8,
+    bound: u8,
+    generation: u8,
+}
+
+/*
+Scores of the positions already searched, indexed by their Zobrist key. The table has a fixed
+size given in megabytes and every key has a single slot, so positions fight for the slots: an
+entry is replaced by a search at least as deep, or by anything once it comes from a previous
+search, so that the deep results of the current search stay.
+
+Mate scores count the plies from the root, which is not the same for a position reached at
+different plies, so they are stored counted from the position itself.
+*/
+pub(super) struct TranspositionTable {
+    entries: Vec<Entry>,
+    generation: u8,
+}
+
+impl TranspositionTable {
+    pub(super) fn new(megabytes: usize) -> TranspositionTable {
+        let size = (megabytes * 1024 * 1024 / mem::size_of::<Entry>()).max(1);
+        TranspositionTable {
+            entries: vec![Entry::default(); size],
+            generation: 0,
+        }
+    }
+
+    pub(super) fn clear(&mut self) {
+        self.entries.fill(Entry::default());
+        self.generation = 0;
+    }
+
+    // Entries stored before this are the first to go
+    pub(super) fn new_search(&mut self) {
+        self.generation = self.generation.wrapping_add(1);
+    }
+
+    fn index(&self, key: u64) -> usize {
+        // Maps the key onto the table without the cost of a division
+        ((key as u128 * self.entries.len() as u128) >> 64) as usize
+    }
+
+    pub(super) fn probe(&self, key: u64, ply: i32) -> Option<TableHit> {
+        let entry = self.entries[self.index(key)];
+        if entry.key != key || key == 0 {
+            return None;
+        }
+        let bound = match entry.bound {
+            0 => Bound::Exact,
+            1 => Bound::Lower,
+            _ => Bound::Upper,
+        };
+        Some(TableHit {
+            depth: entry.depth as u32,
+            score: score_from_table(entry.score as i32, ply),
+            bound,
+            best_move: (entry.best_move != 0).then(|| Move::from_u16(entry.best_move)),
+        })
+    }
+
+    pub(super) fn store(
+        &mut self,
+        key: u64,
+        depth: u32,
+        ply: i32,
+        score: i32,
+        bound: Bound,
+        best_move: Option<Move>,
+    ) {
+        let index = self.index(key);
+        let old = self.entries[index];
+        let depth = depth.min(u8::MAX as u32) as u8;
+        if old.key != 0 && old.generation == self.generation && depth < old.depth {
+            return;
+        }
+
+        // A search that found no best move keeps the one found before in the same position
+        let best_move = match best_move {
+            Some(best_move) => best_move.to_u16(),
+            None if old.key == key => old.best_move,
+            None => 0,
+        };
+        self.entries[index] = Entry {
+            key,
+            best_move,
+            score: score_to_table(score, ply) as i16,
+            depth,
+            bound: bound as u8,
+            generation: self.generation,
+        };
+    }
+}
+
+fn score_to_table(score: i32, ply: i32) -> i32 {
+    if score > MATE_BOUND {
+        score + ply
+    } else if score < -MATE_BOUND {
+        score - ply
+    } else {
+        score
+    }
+}
+
+fn score_from_table(score: i32, ply: i32) -> i32 {
+    if score > MATE_BOUND {
+        score - ply
+    } else if score < -MATE_BOUND {
+        score + ply
+    } else {
+        score
+    }
+}
@@ -0,0 +1,221 @@
+use std::{
+    io::{self, BufRead, Write},
+    sync::atomic::{AtomicBool, Ordering},
+    time::Duration,
+};
+
+use super::{
+    protocol::{self, Next, Protocol},
+    search::MATE_BOUND,
+    Engine, SearchInfo, SearchLimits, SearchResult, DEFAULT_HASH_SIZE, MATE_SCORE, MAX_HASH_SIZE,
+};
+use crate::{Move, STARTING_FEN};
+
+/*
+Speaks the UCI protocol: commands come one per line on the input and the answers are written
+to the output. Searches run on a thread of their own, so that isready and stop are dealt with
+while the engine thinks. The other commands wait for the search to end, as does a quit unless
+the search would never end by itself.
+*/
+pub fn run<R, W>(input: R, output: &mut W) -> io::Result<()>
+where
+    R: BufRead + Send + 'static,
+    W: Write,
+{
+    protocol::run(input, output, &mut Uci)
+}
+
+struct Uci;
+
+impl Protocol for Uci {
+    fn handle_command<W: Write>(
+        &mut self,
+        engine: &mut Engine,
+        line: &str,
+        output: &mut W,
+    ) -> io::Result<Next> {
+        let words: Vec<&str> = line.split_whitespace().collect();
+        if words.first() == Some(&"go") {
+            return Ok(Next::Search(parse_go(&words[1..])));
+        }
+        if handle_command(engine, line, output)? {
+            Ok(Next::Continue)
+        } else {
+            Ok(Next::Quit)
+        }
+    }
+
+    fn command_during_search<W: Write>(
+        &mut self,
+        line: &str,
+        stop: &AtomicBool,
+        output: &mut W,
+    ) -> io::Result<bool> {
+        match line.split_whitespace().next() {
+            Some("isready") => writeln!(output, "readyok")?,
+            Some("stop") => stop.store(true, Ordering::Relaxed),
+            _ => return Ok(false),
+        }
+        Ok(true)
+    }
+
+    fn write_info<W: Write>(&mut self, info: &SearchInfo, output: &mut W) -> io::Result<()> {
+        write_info(output, info)
+    }
+
+    fn search_done<W: Write>(
+        &mut self,
+        _engine: &mut Engine,
+        result: SearchResult,
+        output: &mut W,
+    ) -> io::Result<()> {
+        write_best_move(output, result.best_move)
+    }
+}
+
+// Handles one command. Unlike run, go searches on the calling thread and only returns once
+// the search is over.
+// Returns false once the engine should quit
+pub fn handle_command<W: Write>(
+    engine: &mut Engine,
+    line: &str,
+    output: &mut W,
+) -> io::Result<bool> {
+    let words: Vec<&str> = line.split_whitespace().collect();
+    let Some((&command, arguments)) = words.split_first() else {
+        return Ok(true);
+    };
+
+    match command {
+        "uci" => {
+            writeln!(output, "id name cherris {}", env!("CARGO_PKG_VERSION"))?;
+            writeln!(output, "id author the cherris developers")?;
+            writeln!(
+                output,
+                "option name Hash type spin default {} min 1 max {}",
+                DEFAULT_HASH_SIZE, MAX_HASH_SIZE
+            )?;
+            writeln!(output, "uciok")?;
+        }
+        "isready" => writeln!(output, "readyok")?,
+        "ucinewgame" => engine.new_game(),
+        "position" => {
+            if let Err(error) = set_position(engine, arguments) {
+                writeln!(output, "info string {}", error)?;
+            }
+        }
+        "go" => {
+            let limits = parse_go(arguments);
+            let mut written = Ok(());
+            let result = engine.search_with_info(&limits, |info| {
+                if written.is_ok() {
+                    written = write_info(output, info).and_then(|_| output.flush());
+                }
+            });
+            written?;
+            write_best_move(output, result.best_move)?;
+        }
+        // There is no search to stop once the command is read here
+        "stop" => {}
+        "setoption" => {
+            let (name, value) = parse_setoption(arguments);
+            if let Err(error) = engine.set_option(&name, &value) {
+                writeln!(output, "info string {}", error)?;
+            }
+        }
+        "quit" => return Ok(false),
+        "debug" | "ponderhit" | "register" => {}
+        _ => writeln!(output, "info string unknown command {}", command)?,
+    }
+    output.flush()?;
+    Ok(true)
+}
+
+// info depth 5 score cp 31 nodes 12000 nps 400000 time 30 pv e2e4 e7e5 g1f3 b8c6 f1b5
+fn write_info<W: Write>(output: &mut W, info: &SearchInfo) -> io::Result<()> {
+    // Mates are given in moves, negative when the engine is the one getting mated
+    let score = if info.score > MATE_BOUND {
+        format!("mate {}", (MATE_SCORE - info.score + 1) / 2)
+    } else if info.score < -MATE_BOUND {
+        format!("mate -{}", (MATE_SCORE + info.score) / 2)
+    } else {
+        format!("cp {}", info.score)
+    };
+    let milliseconds = info.time.as_millis() as u64;
+    let nodes_per_second = info.nodes * 1000 / milliseconds.max(1);
+    let variation: Vec<String> = info
+        .principal_variation
+        .iter()
+        .map(|chess_move| chess_move.to_uci())
+        .collect();
+    writeln!(
+        output,
+        "info depth {} score {} nodes {} nps {} time {} pv {}",
+        info.depth,
+        score,
+        info.nodes,
+        nodes_per_second,
+        milliseconds,
+        variation.join(" ")
+    )
+}
+
+// No move is written as 0000, when the side to move is mated or stalemated
+fn write_best_move<W: Write>(output: &mut W, best_move: Option<Move>) -> io::Result<()> {
+    match best_move {
+        Some(best_move) => writeln!(output, "bestmove {}", best_move.to_uci()),
+        None => writeln!(output, "bestmove 0000"),
+    }
+}
+
+// position [startpos | fen <fen>] [moves <move>...]
+fn set_position(engine: &mut Engine, arguments: &[&str]) -> Result<(), String> {
+    let moves_start = arguments.iter().position(|&word| word == "moves");
+    let (setup, moves) = match moves_start {
+        Some(index) => (&arguments[..index], &arguments[index + 1..]),
+        None => (arguments, &[][..]),
+    };
+
+    let fen = match setup.split_first() {
+        Some((&"startpos", [])) => String::from(STARTING_FEN),
+        Some((&"fen", fields)) if !fields.is_empty() => fields.join(" "),
+        _ => return Err(format!("invalid position command: {}", arguments.join(" "))),
+    };
+    engine
+        .set_position(&fen, moves)
+        .map_err(|error| error.to_string())
+}
+
+fn parse_go(arguments: &[&str]) -> SearchLimits {
+    let mut limits = SearchLimits::default();
+    let mut words = arguments.iter();
+    while let Some(&word) = words.next() {
+        let mut number = || words.next().and_then(|value| value.parse::<u64>().ok());
+        match word {
+            "depth" => limits.depth = number().map(|depth| depth as u32),
+            "movetime" => limits.movetime = number().map(Duration::from_millis),
+            "wtime" => limits.wtime = number().map(Duration::from_millis),
+            "btime" => limits.btime = number().map(Duration::from_millis),
+            "winc" => limits.winc = number().map(Duration::from_millis),
+            "binc" => limits.binc = number().map(Duration::from_millis),
+            "movestogo" => limits.movestogo = number().map(|moves| moves as u32),
+            "infinite" => limits.infinite = true,
+            _ => {}
+        }
+    }
+    limits
+}
+
+// setoption name <name, maybe several words> [value <value>]
+fn parse_setoption(arguments: &[&str]) -> (String, String) {
+    let value_start = arguments
+        .iter()
+        .position(|&word| word == "value")
+        .unwrap_or(arguments.len());
+    let name_words = arguments[..value_start]
+        .iter()
+        .skip_while(|&&word| word == "name");
+    let name = name_words.copied().collect::<Vec<&str>>().join(" ");
+    let value = arguments.get(value_start + 1..).unwrap_or(&[]).join(" ");
+    (name, value)
+}
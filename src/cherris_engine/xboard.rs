@@ -0,0 +1,321 @@
+use std::{
+    io::{self, BufRead, Write},
+    sync::atomic::{AtomicBool, Ordering},
+    time::Duration,
+};
+
+use super::{
+    protocol::{self, Next, Protocol},
+    Engine, SearchInfo, SearchLimits, SearchResult,
+};
+use crate::{GameResult, Move, Termination, UciMoveError, STARTING_FEN};
+
+// Commands that take back the right to move from the engine, its search is then given up
+const ABANDON_COMMANDS: [&str; 3] = ["new", "force", "result"];
+
+/*
+Speaks the XBoard (CECP) protocol. Unlike UCI, the engine keeps track of the game itself: the
+GUI sends the moves of the opponent one by one, and the engine answers with its own move whenever
+it is its turn, unless it was put in force mode.
+
+Searches run on a thread of their own like with UCI. While one runs, "?" has the move played at
+once and the commands taking back the right to move end it without a move, the other commands
+wait for the search to end.
+*/
+pub fn run<R, W>(input: R, output: &mut W) -> io::Result<()>
+where
+    R: BufRead + Send + 'static,
+    W: Write,
+{
+    protocol::run(input, output, &mut XBoard::default())
+}
+
+// What the GUI told the engine about the game
+struct XBoard {
+    force: bool,
+    // Whether the thinking output is wanted
+    post: bool,
+    engine_white: bool,
+    max_depth: Option<u32>,
+    move_time: Option<Duration>,
+    moves_per_session: u32,
+    increment: Duration,
+    time_left: Option<Duration>,
+    opponent_time_left: Option<Duration>,
+    // Whether the running search was given up, its move is then not played
+    abandoned: bool,
+}
+
+impl Default for XBoard {
+    fn default() -> Self {
+        XBoard {
+            force: false,
+            post: false,
+            engine_white: false,
+            max_depth: None,
+            move_time: None,
+            moves_per_session: 0,
+            increment: Duration::ZERO,
+            time_left: None,
+            opponent_time_left: None,
+            abandoned: false,
+        }
+    }
+}
+
+impl Protocol for XBoard {
+    // The engine starts thinking after any command that leaves it to move
+    fn handle_command<W: Write>(
+        &mut self,
+        engine: &mut Engine,
+        line: &str,
+        output: &mut W,
+    ) -> io::Result<Next> {
+        let words: Vec<&str> = line.split_whitespace().collect();
+        let Some((&command, arguments)) = words.split_first() else {
+            return Ok(Next::Continue);
+        };
+
+        match command {
+            "protover" => writeln!(
+                output,
+                "feature myname=\"cherris {}\" usermove=1 setboard=1 ping=1 memory=1 colors=0 sigint=0 \
+                 sigterm=0 done=1",
+                env!("CARGO_PKG_VERSION")
+            )?,
+            "new" => {
+                engine.new_game();
+                self.force = false;
+                self.engine_white = false;
+                self.max_depth = None;
+            }
+            "force" | "result" => self.force = true,
+            "go" => {
+                self.force = false;
+                self.engine_white = engine.game().white_turn();
+            }
+            "playother" => {
+                self.force = false;
+                self.engine_white = !engine.game().white_turn();
+            }
+            "usermove" => match arguments.first() {
+                Some(text) => user_move(engine, text, output)?,
+                None => writeln!(output, "Error (missing move): usermove")?,
+            },
+            "undo" => {
+                engine.undo_move();
+            }
+            "remove" => {
+                engine.undo_move();
+                engine.undo_move();
+            }
+            "setboard" => {
+                if engine.set_position(&arguments.join(" "), &[]).is_err() {
+                    writeln!(output, "tellusererror Illegal position")?;
+                    engine.set_position(STARTING_FEN, &[]).ok();
+                }
+            }
+            "level" => match parse_level(arguments) {
+                Some((moves_per_session, base, increment)) => {
+                    self.moves_per_session = moves_per_session;
+                    self.increment = increment;
+                    self.move_time = None;
+                    self.time_left = Some(base);
+                    self.opponent_time_left = Some(base);
+                }
+                None => writeln!(output, "Error (invalid level): {}", line.trim())?,
+            },
+            "st" => match arguments.first().and_then(|seconds| parse_seconds(seconds)) {
+                Some(seconds) => self.move_time = Some(seconds),
+                None => writeln!(output, "Error (invalid time): {}", line.trim())?,
+            },
+            "sd" => match arguments.first().and_then(|depth| depth.parse().ok()) {
+                Some(depth) => self.max_depth = Some(depth),
+                None => writeln!(output, "Error (invalid depth): {}", line.trim())?,
+            },
+            // Clocks are given in centiseconds
+            "time" | "otim" => match arguments.first().and_then(|time| time.parse::<u64>().ok()) {
+                Some(centiseconds) => {
+                    let clock = Some(Duration::from_millis(centiseconds * 10));
+                    if command == "time" {
+                        self.time_left = clock;
+                    } else {
+                        self.opponent_time_left = clock;
+                    }
+                }
+                None => writeln!(output, "Error (invalid time): {}", line.trim())?,
+            },
+            // Megabytes the engine may use, all of them go to the transposition table
+            "memory" => {
+                let size = arguments.first().copied().unwrap_or("");
+                if let Err(error) = engine.set_option("Hash", size) {
+                    writeln!(output, "Error ({}): {}", error, line.trim())?;
+                }
+            }
+            "post" => self.post = true,
+            "nopost" => self.post = false,
+            "ping" => writeln!(output, "pong {}", arguments.join(" "))?,
+            "quit" => return Ok(Next::Quit),
+            // There is no search to cut short once the command is read here
+            "?" => {}
+            "xboard" | "accepted" | "rejected" | "hard" | "easy" | "random"
+            | "computer" | "name" | "rating" | "ics" | "draw" | "hint" | "bk" => {}
+            // Moves may also come without usermove when the feature was not accepted
+            _ => user_move(engine, command, output)?,
+        }
+        output.flush()?;
+
+        if self.engine_to_move(engine) {
+            Ok(Next::Search(self.limits(engine)))
+        } else {
+            Ok(Next::Continue)
+        }
+    }
+
+    fn command_during_search<W: Write>(
+        &mut self,
+        line: &str,
+        stop: &AtomicBool,
+        _output: &mut W,
+    ) -> io::Result<bool> {
+        let command = line.split_whitespace().next().unwrap_or("");
+        if command == "?" {
+            stop.store(true, Ordering::Relaxed);
+            return Ok(true);
+        }
+        if ABANDON_COMMANDS.contains(&command) {
+            stop.store(true, Ordering::Relaxed);
+            self.abandoned = true;
+        }
+        Ok(false)
+    }
+
+    fn write_info<W: Write>(&mut self, info: &SearchInfo, output: &mut W) -> io::Result<()> {
+        if self.post {
+            write_thinking(output, info)?;
+        }
+        Ok(())
+    }
+
+    fn search_done<W: Write>(
+        &mut self,
+        engine: &mut Engine,
+        result: SearchResult,
+        output: &mut W,
+    ) -> io::Result<()> {
+        if let (Some(best_move), false) = (result.best_move, self.abandoned) {
+            engine.play_move(best_move);
+            writeln!(output, "move {}", best_move.to_uci())?;
+            write_result(engine, output)?;
+        }
+        self.abandoned = false;
+        Ok(())
+    }
+}
+
+impl XBoard {
+    fn engine_to_move(&self, engine: &Engine) -> bool {
+        !self.force && !engine.game().game_done() && engine.game().white_turn() == self.engine_white
+    }
+
+    fn limits(&self, engine: &Engine) -> SearchLimits {
+        let mut limits = SearchLimits {
+            depth: self.max_depth,
+            movetime: self.move_time,
+            ..SearchLimits::default()
+        };
+        if self.move_time.is_some() {
+            return limits;
+        }
+
+        let (white_time, black_time) = if engine.game().white_turn() {
+            (self.time_left, self.opponent_time_left)
+        } else {
+            (self.opponent_time_left, self.time_left)
+        };
+        limits.wtime = white_time;
+        limits.btime = black_time;
+        limits.winc = Some(self.increment);
+        limits.binc = Some(self.increment);
+        if self.moves_per_session > 0 {
+            let moves_played = (engine.game().fullmove_number() - 1) % self.moves_per_session;
+            limits.movestogo = Some(self.moves_per_session - moves_played);
+        }
+        limits
+    }
+}
+
+// Plays a move of the opponent of the engine
+fn user_move<W: Write>(engine: &mut Engine, text: &str, output: &mut W) -> io::Result<()> {
+    if engine.game().game_done() {
+        return writeln!(output, "Illegal move (game is over): {}", text);
+    }
+    match Move::from_uci(engine.game(), text) {
+        Ok(chess_move) => {
+            engine.play_move(chess_move);
+            write_result(engine, output)
+        }
+        Err(UciMoveError::IllegalMove(_)) => writeln!(output, "Illegal move: {}", text),
+        Err(UciMoveError::Syntax(_)) => writeln!(output, "Error (unknown command): {}", text),
+    }
+}
+
+// level <moves per session> <base time in minutes or minutes:seconds> <increment in seconds>
+fn parse_level(arguments: &[&str]) -> Option<(u32, Duration, Duration)> {
+    let [moves_per_session, base, increment] = arguments else {
+        return None;
+    };
+    let (minutes, seconds) = base.split_once(':').unwrap_or((base, "0"));
+    let base = minutes.parse::<u64>().ok()? * 60 + seconds.parse::<u64>().ok()?;
+    Some((
+        moves_per_session.parse().ok()?,
+        Duration::from_secs(base),
+        parse_seconds(increment)?,
+    ))
+}
+
+fn parse_seconds(seconds: &str) -> Option<Duration> {
+    Duration::try_from_secs_f64(seconds.parse().ok()?).ok()
+}
+
+// <depth> <score in centipawns> <time in centiseconds> <nodes> <principal variation>
+fn write_thinking<W: Write>(output: &mut W, info: &SearchInfo) -> io::Result<()> {
+    let variation: Vec<String> = info
+        .principal_variation
+        .iter()
+        .map(|chess_move| chess_move.to_uci())
+        .collect();
+    writeln!(
+        output,
+        "{} {} {} {} {}",
+        info.depth,
+        info.score,
+        info.time.as_millis() / 10,
+        info.nodes,
+        variation.join(" ")
+    )
+}
+
+// Tells the GUI when the last move ended the game
+fn write_result<W: Write>(engine: &Engine, output: &mut W) -> io::Result<()> {
+    let game = engine.game();
+    let (Some(result), Some(termination)) = (game.result(), game.termination()) else {
+        return Ok(());
+    };
+    let score = match result {
+        GameResult::WhiteWins => "1-0",
+        GameResult::BlackWins => "0-1",
+        GameResult::Draw => "1/2-1/2",
+    };
+    let reason = match (termination, result) {
+        (Termination::Checkmate, GameResult::WhiteWins) => "White mates",
+        (Termination::Checkmate, _) => "Black mates",
+        (Termination::Stalemate, _) => "Stalemate",
+        (Termination::InsufficientMaterial, _) => "Insufficient material",
+        (Termination::FiftyMoveRule, _) => "50-move rule",
+        (Termination::SeventyFiveMoveRule, _) => "75-move rule",
+        (Termination::ThreefoldRepetition, _) => "Threefold repetition",
+        (Termination::FivefoldRepetition, _) => "Fivefold repetition",
+    };
+    writeln!(output, "{} {{{}}}", score, reason)
+}
@@ -0,0 +1,171 @@
+use crate::{bitboard::squares, Board, PieceType};
+
+/*
+Static evaluation in centipawns. Every piece is worth its material value plus a bonus or malus
+for the square it stands on, both in two flavours: one for the middlegame and one for the
+endgame. The two scores are blended by the game phase, worked out from the pieces other than
+pawns and kings that are still on the board, so that the king hides while the queens are around
+and walks to the centre once they are gone.
+
+The tables are written from white's side, rank 8 first like Board::state, and are mirrored for
+black. They follow Tomasz Michniewski's simplified evaluation function.
+*/
+const KINDS: [PieceType; 6] = [
+    PieceType::Pawn,
+    PieceType::Knight,
+    PieceType::Bishop,
+    PieceType::Rook,
+    PieceType::Queen,
+    PieceType::King,
+];
+
+const MIDDLEGAME_VALUES: [i32; 6] = [100, 320, 330, 500, 900, 0];
+const ENDGAME_VALUES: [i32; 6] = [120, 300, 320, 530, 950, 0];
+
+// How much each piece counts towards the middlegame, all of them make 24
+const PHASE_WEIGHTS: [i32; 6] = [0, 1, 1, 2, 4, 0];
+const MIDDLEGAME_PHASE: i32 = 24;
+
+#[rustfmt::skip]
+const PAWN_MIDDLEGAME: [i32; 64] = [
+      0,   0,   0,   0,   0,   0,   0,   0,
+     50,  50,  50,  50,  50,  50,  50,  50,
+     10,  10,  20,  30,  30,  20,  10,  10,
+      5,   5,  10,  25,  25,  10,   5,   5,
+      0,   0,   0,  20,  20,   0,   0,   0,
+      5,  -5, -10,   0,   0, -10,  -5,   5,
+      5,  10,  10, -20, -20,  10,  10,   5,
+      0,   0,   0,   0,   0,   0,   0,   0,
+];
+
+// Passed or not, a pawn close to promotion is what endgames are about
+#[rustfmt::skip]
+const PAWN_ENDGAME: [i32; 64] = [
+      0,   0,   0,   0,   0,   0,   0,   0,
+     80,  80,  80,  80,  80,  80,  80,  80,
+     50,  50,  50,  50,  50,  50,  50,  50,
+     30,  30,  30,  30,  30,  30,  30,  30,
+     20,  20,  20,  20,  20,  20,  20,  20,
+     10,  10,  10,  10,  10,  10,  10,  10,
+     10,  10,  10,  10,  10,  10,  10,  10,
+      0,   0,   0,   0,   0,   0,   0,   0,
+];
+
+#[rustfmt::skip]
+const KNIGHT_TABLE: [i32; 64] = [
+    -50, -40, -30, -30, -30, -30, -40, -50,
+    -40, -20,   0,   0,   0,   0, -20, -40,
+    -30,   0,  10,  15,  15,  10,   0, -30,
+    -30,   5,  15,  20,  20,  15,   5, -30,
+    -30,   0,  15,  20,  20,  15,   0, -30,
+    -30,   5,  10,  15,  15,  10,   5, -30,
+    -40, -20,   0,   5,   5,   0, -20, -40,
+    -50, -40, -30, -30, -30, -30, -40, -50,
+];
+
+#[rustfmt::skip]
+const BISHOP_TABLE: [i32; 64] = [
+    -20, -10, -10, -10, -10, -10, -10, -20,
+    -10,   0,   0,   0,   0,   0,   0, -10,
+    -10,   0,   5,  10,  10,   5,   0, -10,
+    -10,   5,   5,  10,  10,   5,   5, -10,
+    -10,   0,  10,  10,  10,  10,   0, -10,
+    -10,  10,  10,  10,  10,  10,  10, -10,
+    -10,   5,   0,   0,   0,   0,   5, -10,
+    -20, -10, -10, -10, -10, -10, -10, -20,
+];
+
+#[rustfmt::skip]
+const ROOK_TABLE: [i32; 64] = [
+      0,   0,   0,   0,   0,   0,   0,   0,
+      5,  10,  10,  10,  10,  10,  10,   5,
+     -5,   0,   0,   0,   0,   0,   0,  -5,
+     -5,   0,   0,   0,   0,   0,   0,  -5,
+     -5,   0,   0,   0,   0,   0,   0,  -5,
+     -5,   0,   0,   0,   0,   0,   0,  -5,
+     -5,   0,   0,   0,   0,   0,   0,  -5,
+      0,   0,   0,   5,   5,   0,   0,   0,
+];
+
+#[rustfmt::skip]
+const QUEEN_TABLE: [i32; 64] = [
+    -20, -10, -10,  -5,  -5, -10, -10, -20,
+    -10,   0,   0,   0,   0,   0,   0, -10,
+    -10,   0,   5,   5,   5,   5,   0, -10,
+     -5,   0,   5,   5,   5,   5,   0,  -5,
+      0,   0,   5,   5,   5,   5,   0,  -5,
+    -10,   5,   5,   5,   5,   5,   0, -10,
+    -10,   0,   5,   0,   0,   0,   0, -10,
+    -20, -10, -10,  -5,  -5, -10, -10, -20,
+];
+
+#[rustfmt::skip]
+const KING_MIDDLEGAME: [i32; 64] = [
+    -30, -40, -40, -50, -50, -40, -40, -30,
+    -30, -40, -40, -50, -50, -40, -40, -30,
+    -30, -40, -40, -50, -50, -40, -40, -30,
+    -30, -40, -40, -50, -50, -40, -40, -30,
+    -20, -30, -30, -40, -40, -30, -30, -20,
+    -10, -20, -20, -20, -20, -20, -20, -10,
+     20,  20,   0,   0,   0,   0,  20,  20,
+     20,  30,  10,   0,   0,  10,  30,  20,
+];
+
+#[rustfmt::skip]
+const KING_ENDGAME: [i32; 64] = [
+    -50, -40, -30, -20, -20, -30, -40, -50,
+    -30, -20, -10,   0,   0, -10, -20, -30,
+    -30, -10,  20,  30,  30,  20, -10, -30,
+    -30, -10,  30,  40,  40,  30, -10, -30,
+    -30, -10,  30,  40,  40,  30, -10, -30,
+    -30, -10,  20,  30,  30,  20, -10, -30,
+    -30, -30,   0,   0,   0,   0, -30, -30,
+    -50, -30, -30, -30, -30, -30, -30, -50,
+];
+
+const MIDDLEGAME_TABLES: [&[i32; 64]; 6] = [
+    &PAWN_MIDDLEGAME,
+    &KNIGHT_TABLE,
+    &BISHOP_TABLE,
+    &ROOK_TABLE,
+    &QUEEN_TABLE,
+    &KING_MIDDLEGAME,
+];
+const ENDGAME_TABLES: [&[i32; 64]; 6] = [
+    &PAWN_ENDGAME,
+    &KNIGHT_TABLE,
+    &BISHOP_TABLE,
+    &ROOK_TABLE,
+    &QUEEN_TABLE,
+    &KING_ENDGAME,
+];
+
+impl Board {
+    // Score of the position in centipawns, positive when the side to move is better
+    pub fn evaluate(&self, white_turn: bool) -> i32 {
+        let bitboards = self.bitboards();
+        let (mut middlegame, mut endgame, mut phase) = (0, 0, 0);
+        for (kind, &class) in KINDS.iter().enumerate() {
+            for is_white in [true, false] {
+                let sign = if is_white { 1 } else { -1 };
+                for index in squares(bitboards.pieces(is_white, class)) {
+                    // Flipping the rows turns a black square into the matching white one
+                    let square = if is_white { index } else { index ^ 56 };
+                    middlegame +=
+                        sign * (MIDDLEGAME_VALUES[kind] + MIDDLEGAME_TABLES[kind][square]);
+                    endgame += sign * (ENDGAME_VALUES[kind] + ENDGAME_TABLES[kind][square]);
+                    phase += PHASE_WEIGHTS[kind];
+                }
+            }
+        }
+
+        // Promotions can bring more pieces than at the start
+        let phase = phase.min(MIDDLEGAME_PHASE);
+        let score = (middlegame * phase + endgame * (MIDDLEGAME_PHASE - phase)) / MIDDLEGAME_PHASE;
+        if white_turn {
+            score
+        } else {
+            -score
+        }
+    }
+}
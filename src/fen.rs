@@ -0,0 +1,413 @@
+use std::fmt;
+
+use crate::{
+    position_helper, BasicPiece, Piece, PieceType, BISHOP, CASTLE_BLACK_KINGSIDE,
+    CASTLE_BLACK_QUEENSIDE, CASTLE_WHITE_KINGSIDE, CASTLE_WHITE_QUEENSIDE, KING, KNIGHT, PAWN_BIT,
+    PIECE_BIT, QUEEN, ROOK, WHITE_BIT,
+};
+
+pub const STARTING_FEN: &str = "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1";
+
+/// Error returned when a FEN string cannot be parsed. Each variant names the
+/// field that was rejected and carries the reason, except `OpponentInCheck`
+/// for a position that could not have been reached.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FenError {
+    FieldCount(usize),
+    PiecePlacement(String),
+    SideToMove(String),
+    CastlingRights(String),
+    EnPassant(String),
+    HalfmoveClock(String),
+    FullmoveNumber(String),
+    OpponentInCheck,
+}
+
+impl fmt::Display for FenError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FenError::FieldCount(count) => {
+                write!(f, "FEN must have 4 or 6 fields, found {}", count)
+            }
+            FenError::PiecePlacement(reason) => write!(f, "invalid piece placement: {}", reason),
+            FenError::SideToMove(reason) => write!(f, "invalid side to move: {}", reason),
+            FenError::CastlingRights(reason) => write!(f, "invalid castling rights: {}", reason),
+            FenError::EnPassant(reason) => write!(f, "invalid en passant square: {}", reason),
+            FenError::HalfmoveClock(reason) => write!(f, "invalid halfmove clock: {}", reason),
+            FenError::FullmoveNumber(reason) => write!(f, "invalid fullmove number: {}", reason),
+            FenError::OpponentInCheck => {
+                write!(
+                    f,
+                    "the side not to move is in check, its king could be taken"
+                )
+            }
+        }
+    }
+}
+
+impl std::error::Error for FenError {}
+
+/// All the fields of a FEN string, already converted to the crate's encodings.
+pub(crate) struct FenFields {
+    pub state: [u8; 64],
+    pub white_turn: bool,
+    pub castling_rights: u8,
+    pub en_passant: Option<u8>,
+    pub halfmove_clock: u32,
+    pub fullmove_number: u32,
+}
+
+// The clocks are optional so that EPD-style strings with only the first four fields are accepted
+pub(crate) fn parse(fen: &str) -> Result<FenFields, FenError> {
+    let fields: Vec<&str> = fen.split_whitespace().collect();
+    if fields.len() != 4 && fields.len() != 6 {
+        return Err(FenError::FieldCount(fields.len()));
+    }
+
+    let state = parse_placement(fields[0])?;
+
+    let white_turn = match fields[1] {
+        "w" => true,
+        "b" => false,
+        other => {
+            return Err(FenError::SideToMove(format!(
+                "expected 'w' or 'b', found '{}'",
+                other
+            )))
+        }
+    };
+
+    let castling_rights = parse_castling(fields[2])? & castling_rights_on_board(&state);
+    let en_passant = parse_en_passant(fields[3], white_turn, &state)?;
+
+    let (halfmove_clock, fullmove_number) = if fields.len() == 6 {
+        let halfmove_clock = fields[4]
+            .parse::<u32>()
+            .map_err(|_| FenError::HalfmoveClock(format!("'{}' is not a number", fields[4])))?;
+        let fullmove_number = fields[5]
+            .parse::<u32>()
+            .map_err(|_| FenError::FullmoveNumber(format!("'{}' is not a number", fields[5])))?;
+        if fullmove_number == 0 {
+            return Err(FenError::FullmoveNumber(String::from("must start at 1")));
+        }
+        (halfmove_clock, fullmove_number)
+    } else {
+        (0, 1)
+    };
+
+    Ok(FenFields {
+        state,
+        white_turn,
+        castling_rights,
+        en_passant,
+        halfmove_clock,
+        fullmove_number,
+    })
+}
+
+// Pieces get the same unique keys as in Board::init(): the first bishop, knight and rook found
+// for a colour (scanning from a8 to h1) take the lower key, pawns are numbered from 8 in the same
+// order. Pieces beyond the original set are encoded as promoted pawns, taking the pawn indices
+// left free by the pawns on the board.
+pub(crate) fn parse_placement(placement: &str) -> Result<[u8; 64], FenError> {
+    let mut state = [0u8; 64];
+    // Next free key for each colour, [black, white]
+    let mut next_bishop = [BISHOP; 2];
+    let mut next_knight = [KNIGHT; 2];
+    let mut next_rook = [ROOK; 2];
+    let mut next_pawn = [PAWN_BIT; 2];
+    let mut next_queen = [QUEEN; 2];
+    let mut kings = [0u8; 2];
+    // (index, colour, promotion char) of the pieces that do not fit the original keys
+    let mut promoted: Vec<(usize, usize, char)> = Vec::new();
+
+    let ranks: Vec<&str> = placement.split('/').collect();
+    if ranks.len() != 8 {
+        return Err(FenError::PiecePlacement(format!(
+            "expected 8 ranks, found {}",
+            ranks.len()
+        )));
+    }
+
+    for (row, rank) in ranks.iter().enumerate() {
+        let mut col = 0usize;
+        for c in rank.chars() {
+            if let Some(empty) = c.to_digit(10) {
+                if empty == 0 || empty > 8 {
+                    return Err(FenError::PiecePlacement(format!(
+                        "invalid empty square count '{}' in rank {}",
+                        c,
+                        8 - row
+                    )));
+                }
+                col += empty as usize;
+                continue;
+            }
+
+            if col >= 8 {
+                return Err(FenError::PiecePlacement(format!(
+                    "rank {} has more than 8 squares",
+                    8 - row
+                )));
+            }
+
+            let is_white = c.is_ascii_uppercase();
+            let colour = is_white as usize;
+            let lowercase = c.to_ascii_lowercase();
+            let key = match lowercase {
+                'k' => {
+                    kings[colour] += 1;
+                    if kings[colour] > 1 {
+                        return Err(too_many(is_white, "kings"));
+                    }
+                    Some(KING)
+                }
+                'q' => take_key(&mut next_queen[colour], QUEEN + 1),
+                'b' => take_key(&mut next_bishop[colour], BISHOP + 2),
+                'n' => take_key(&mut next_knight[colour], KNIGHT + 2),
+                'r' => take_key(&mut next_rook[colour], ROOK + 2),
+                'p' => {
+                    if row == 0 || row == 7 {
+                        return Err(FenError::PiecePlacement(format!(
+                            "pawn on rank {}",
+                            8 - row
+                        )));
+                    }
+                    Some(
+                        take_key(&mut next_pawn[colour], PAWN_BIT + 8)
+                            .ok_or_else(|| too_many(is_white, "pawns"))?,
+                    )
+                }
+                _ => return Err(FenError::PiecePlacement(format!("unknown piece '{}'", c))),
+            };
+
+            let colour_bit = if is_white { WHITE_BIT } else { 0 };
+            match key {
+                Some(key) => state[row * 8 + col] = PIECE_BIT + colour_bit + key,
+                None => promoted.push((row * 8 + col, colour, lowercase)),
+            }
+            col += 1;
+        }
+
+        if col != 8 {
+            return Err(FenError::PiecePlacement(format!(
+                "rank {} has {} squares instead of 8",
+                8 - row,
+                col
+            )));
+        }
+    }
+
+    for (index, colour, c) in promoted {
+        let is_white = colour == 1;
+        let pawn_key = take_key(&mut next_pawn[colour], PAWN_BIT + 8)
+            .ok_or_else(|| too_many(is_white, "pieces"))?;
+        let class = PieceType::from_promotion_char(c).expect("only promotable pieces are kept");
+        let colour_bit = if is_white { WHITE_BIT } else { 0 };
+        let pawn = Piece::init_from_binary(PIECE_BIT + colour_bit + pawn_key);
+        state[index] = pawn.promoted_binary(&class).expect("promotable piece");
+    }
+
+    if kings[0] != 1 || kings[1] != 1 {
+        return Err(FenError::PiecePlacement(String::from(
+            "each side needs exactly one king",
+        )));
+    }
+
+    Ok(state)
+}
+
+fn take_key(next: &mut u8, limit: u8) -> Option<u8> {
+    if *next >= limit {
+        return None;
+    }
+    let key = *next;
+    *next += 1;
+    Some(key)
+}
+
+fn too_many(is_white: bool, pieces: &str) -> FenError {
+    let colour = if is_white { "white" } else { "black" };
+    FenError::PiecePlacement(format!("too many {} {} to encode", colour, pieces))
+}
+
+// A castling flag is kept only while its king and rook are on their home squares, so that a FEN
+// listing rights it cannot have is read as the position it really is
+fn castling_rights_on_board(state: &[u8; 64]) -> u8 {
+    let is_at = |index: usize, class: PieceType, is_white: bool| {
+        state[index] != 0 && {
+            let piece = Piece::init_from_binary(state[index]);
+            piece.class == class && piece.is_white == is_white
+        }
+    };
+
+    let mut rights = 0u8;
+    for (is_white, row, kingside, queenside) in [
+        (true, 56, CASTLE_WHITE_KINGSIDE, CASTLE_WHITE_QUEENSIDE),
+        (false, 0, CASTLE_BLACK_KINGSIDE, CASTLE_BLACK_QUEENSIDE),
+    ] {
+        if !is_at(row + 4, PieceType::King, is_white) {
+            continue;
+        }
+        if is_at(row + 7, PieceType::Rook, is_white) {
+            rights |= kingside;
+        }
+        if is_at(row, PieceType::Rook, is_white) {
+            rights |= queenside;
+        }
+    }
+    rights
+}
+
+fn parse_castling(castling: &str) -> Result<u8, FenError> {
+    if castling == "-" {
+        return Ok(0);
+    }
+
+    let mut rights = 0u8;
+    for c in castling.chars() {
+        let right = match c {
+            'K' => CASTLE_WHITE_KINGSIDE,
+            'Q' => CASTLE_WHITE_QUEENSIDE,
+            'k' => CASTLE_BLACK_KINGSIDE,
+            'q' => CASTLE_BLACK_QUEENSIDE,
+            _ => return Err(FenError::CastlingRights(format!("unknown flag '{}'", c))),
+        };
+        if rights & right != 0 {
+            return Err(FenError::CastlingRights(format!("repeated flag '{}'", c)));
+        }
+        rights |= right;
+    }
+
+    Ok(rights)
+}
+
+fn parse_en_passant(
+    square: &str,
+    white_turn: bool,
+    state: &[u8; 64],
+) -> Result<Option<u8>, FenError> {
+    if square == "-" {
+        return Ok(None);
+    }
+
+    let mut chars = square.chars();
+    let (Some(letter), Some(number), None) = (chars.next(), chars.next(), chars.next()) else {
+        return Err(FenError::EnPassant(format!("'{}' is not a square", square)));
+    };
+    if !('a'..='h').contains(&letter) || !('1'..='8').contains(&number) {
+        return Err(FenError::EnPassant(format!("'{}' is not a square", square)));
+    }
+
+    // The target square is behind the pawn that just made a double push
+    let expected_rank = if white_turn { '6' } else { '3' };
+    if number != expected_rank {
+        return Err(FenError::EnPassant(format!(
+            "'{}' is not on rank {}",
+            square, expected_rank
+        )));
+    }
+
+    // The pawn that made the double push must still stand in front of the target square
+    let target = position_helper::letter_to_position_byte(square.to_string());
+    let index = position_helper::position_byte_to_index(target);
+    let pawn_index = if white_turn { index + 8 } else { index - 8 };
+    let pushed_pawn = state[pawn_index] != 0 && {
+        let piece = Piece::init_from_binary(state[pawn_index]);
+        piece.class == PieceType::Pawn && piece.is_white != white_turn
+    };
+    if !pushed_pawn {
+        return Err(FenError::EnPassant(format!(
+            "no pawn in front of '{}' made a double push",
+            square
+        )));
+    }
+
+    Ok(Some(target))
+}
+
+pub(crate) fn format(
+    state: &[u8; 64],
+    white_turn: bool,
+    castling_rights: u8,
+    en_passant: Option<u8>,
+    halfmove_clock: u32,
+    fullmove_number: u32,
+) -> String {
+    let side = if white_turn { "w" } else { "b" };
+    let en_passant = match en_passant {
+        Some(position) => position_helper::position_byte_to_letter(position),
+        None => String::from("-"),
+    };
+
+    format!(
+        "{} {} {} {} {} {}",
+        format_placement(state),
+        side,
+        format_castling(castling_rights),
+        en_passant,
+        halfmove_clock,
+        fullmove_number
+    )
+}
+
+pub(crate) fn format_placement(state: &[u8; 64]) -> String {
+    let mut placement = String::new();
+    for row in 0..8 {
+        let mut empty = 0;
+        for col in 0..8 {
+            let piece_byte = state[row * 8 + col];
+            if piece_byte == 0 {
+                empty += 1;
+                continue;
+            }
+            if empty > 0 {
+                placement.push_str(&empty.to_string());
+                empty = 0;
+            }
+            placement.push(piece_char(&Piece::init_from_binary(piece_byte)));
+        }
+        if empty > 0 {
+            placement.push_str(&empty.to_string());
+        }
+        if row < 7 {
+            placement.push('/');
+        }
+    }
+    placement
+}
+
+fn piece_char(piece: &Piece) -> char {
+    let c = match piece.class {
+        PieceType::Pawn => 'p',
+        PieceType::Knight => 'n',
+        PieceType::Bishop => 'b',
+        PieceType::Rook => 'r',
+        PieceType::Queen => 'q',
+        PieceType::King => 'k',
+    };
+    if piece.is_white {
+        c.to_ascii_uppercase()
+    } else {
+        c
+    }
+}
+
+fn format_castling(castling_rights: u8) -> String {
+    let flags = [
+        (CASTLE_WHITE_KINGSIDE, 'K'),
+        (CASTLE_WHITE_QUEENSIDE, 'Q'),
+        (CASTLE_BLACK_KINGSIDE, 'k'),
+        (CASTLE_BLACK_QUEENSIDE, 'q'),
+    ];
+    let castling: String = flags
+        .iter()
+        .filter(|(flag, _)| castling_rights & flag != 0)
+        .map(|(_, c)| *c)
+        .collect();
+    if castling.is_empty() {
+        String::from("-")
+    } else {
+        castling
+    }
+}
@@ -1,109 +1,684 @@
 use std::{collections::HashMap, io, vec};
 
+mod bitboard;
+pub mod cherris_engine;
+mod evaluation;
+mod fen;
+mod moves;
+mod perft;
+mod pgn;
+mod san;
+mod zobrist;
+pub use bitboard::Bitboards;
+use cherris_engine::{Engine, SearchLimits};
+pub use fen::{FenError, STARTING_FEN};
+pub use moves::{Move, UciMoveError};
+pub use pgn::{read_pgn, write_pgn, PgnError, PgnGame};
+pub use san::SanError;
+
 const PIECE_BIT: u8 = 128u8;
 const WHITE_BIT: u8 = 64u8;
 const PAWN_BIT: u8 = 8u8;
 const CHECK_PIECE: u8 = 0b00001111;
+const PROMOTED_BIT: u8 = 32u8;
+const PROMOTION_MASK: u8 = 0b00011000;
+const PAWN_INDEX_MASK: u8 = 0b00000111;
 const KING: u8 = 0u8;
 const QUEEN: u8 = 1u8;
 const BISHOP: u8 = 2u8;
 const KNIGHT: u8 = 4u8;
 const ROOK: u8 = 6u8;
-const ROW: u8 = 16u8;
-const COL: u8 = 1u8;
 
+pub const CASTLE_WHITE_KINGSIDE: u8 = 1u8;
+pub const CASTLE_WHITE_QUEENSIDE: u8 = 2u8;
+pub const CASTLE_BLACK_KINGSIDE: u8 = 4u8;
+pub const CASTLE_BLACK_QUEENSIDE: u8 = 8u8;
+const CASTLE_ALL: u8 = 0b00001111;
+
+// (row, col) steps, rows grow towards white's side of the board
+const ROOK_DIRECTIONS: [(i8, i8); 4] = [(-1, 0), (1, 0), (0, -1), (0, 1)];
+const BISHOP_DIRECTIONS: [(i8, i8); 4] = [(-1, -1), (-1, 1), (1, -1), (1, 1)];
+const KING_DIRECTIONS: [(i8, i8); 8] = [
+    (-1, 0),
+    (1, 0),
+    (0, -1),
+    (0, 1),
+    (-1, -1),
+    (-1, 1),
+    (1, -1),
+    (1, 1),
+];
+const KNIGHT_DIRECTIONS: [(i8, i8); 8] = [
+    (-2, -1),
+    (-2, 1),
+    (-1, -2),
+    (-1, 2),
+    (1, -2),
+    (1, 2),
+    (2, -1),
+    (2, 1),
+];
+
+#[derive(Clone)]
 pub struct Game {
     white_turn: bool,
-    moves_done: Vec<u32>,
+    moves_done: Vec<Move>,
+    undo_history: Vec<UndoInfo>, // one entry per move in moves_done
     board: Board,
-    game_done: bool,
+    outcome: Option<(GameResult, Termination)>,
+    castling_rights: u8,        // CASTLE_* flags still available
+    en_passant: Option<u8>,     // positionByte a pawn can capture onto
+    halfmove_clock: u32,        // plies since the last capture or pawn move
+    fullmove_number: u32,       // starts at 1, incremented after black moves
+    position_history: Vec<u64>, // zobrist_key() of every position reached
+    start_fen: String,          // position before the first move of moves_done
 }
 
 pub trait ChessGame {
     fn play(&mut self);
     fn play_move(&mut self, initial_position: u8, final_position: u8) -> bool;
+    fn play_move_with_promotion(
+        &mut self,
+        initial_position: u8,
+        final_position: u8,
+        promotion: PieceType,
+    ) -> bool;
+    fn make_move(&mut self, chess_move: Move) -> bool;
+    fn undo_move(&mut self) -> Option<Move>;
 }
 
 impl Game {
     pub fn init() -> Game {
         let mut board = Board::init();
         board.update_hashmap();
-        Game {
+        let mut game = Game {
             white_turn: true,
             moves_done: vec![],
+            undo_history: vec![],
             board,
-            game_done: false,
+            outcome: None,
+            castling_rights: CASTLE_ALL,
+            en_passant: None,
+            halfmove_clock: 0,
+            fullmove_number: 1,
+            position_history: vec![],
+            start_fen: String::from(STARTING_FEN),
+        };
+        game.position_history.push(game.zobrist_key());
+        game
+    }
+
+    pub fn from_fen(fen: &str) -> Result<Game, FenError> {
+        let fields = fen::parse(fen)?;
+        let mut board = Board {
+            pieces: HashMap::new(),
+            state: fields.state,
+            bitboards: Bitboards::default(),
+            zobrist_key: 0,
+        };
+        board.update_hashmap();
+
+        // The previous move cannot have left its own king in check
+        if board
+            .king_position(!fields.white_turn)
+            .is_some_and(|king| board.is_square_attacked(king, fields.white_turn))
+        {
+            return Err(FenError::OpponentInCheck);
         }
+
+        let mut game = Game {
+            white_turn: fields.white_turn,
+            moves_done: vec![],
+            undo_history: vec![],
+            board,
+            outcome: None,
+            castling_rights: fields.castling_rights,
+            en_passant: fields.en_passant,
+            halfmove_clock: fields.halfmove_clock,
+            fullmove_number: fields.fullmove_number,
+            position_history: vec![],
+            start_fen: String::new(),
+        };
+        game.start_fen = game.to_fen();
+        game.position_history.push(game.zobrist_key());
+        // The position might already be over
+        game.update_outcome();
+        Ok(game)
     }
-}
 
-impl ChessGame for Game {
-    fn play(&mut self) {
-        while !self.game_done {
+    // Plays a move already known to be legal, without looking for the end of the game
+    pub(crate) fn apply_move(&mut self, chess_move: Move) {
+        let initial_position = chess_move.initial_position();
+        let piece = Piece::init_from_binary(
+            self.board.state[position_helper::position_byte_to_index(initial_position)],
+        );
+        let final_position = chess_move.final_position();
+
+        // update the board, remembering what is needed to take the move back
+        let t_piece = self.board.make_move(chess_move);
+        self.undo_history.push(UndoInfo {
+            taken_piece: t_piece,
+            castling_rights: self.castling_rights,
+            en_passant: self.en_passant,
+            halfmove_clock: self.halfmove_clock,
+        });
+        self.castling_rights &=
+            !(castling_rights_lost(initial_position) | castling_rights_lost(final_position));
+
+        //End of turn
+        if t_piece != 0 || piece.class == PieceType::Pawn {
+            self.halfmove_clock = 0;
+        } else {
+            self.halfmove_clock += 1;
+        }
+        if !self.white_turn {
+            self.fullmove_number += 1;
+        }
+        // After a double push the skipped square can be taken en passant next turn
+        self.en_passant = if chess_move.is_double_pawn_push() {
+            Some((initial_position + final_position) / 2)
+        } else {
+            None
+        };
+        self.moves_done.push(chess_move);
+        self.white_turn = !self.white_turn;
+        self.position_history.push(self.zobrist_key());
+    }
+
+    pub fn result(&self) -> Option<GameResult> {
+        self.outcome.map(|(result, _)| result)
+    }
+
+    pub fn termination(&self) -> Option<Termination> {
+        self.outcome.map(|(_, termination)| termination)
+    }
+
+    pub fn game_done(&self) -> bool {
+        self.outcome.is_some()
+    }
+
+    // Ends the game when the side to move has no legal move left, or when one of the draw
+    // rules that apply without a claim is met. The game goes on otherwise, even if the position
+    // it was set up from was over.
+    fn update_outcome(&mut self) {
+        self.outcome = if self.legal_moves().is_empty() {
+            if self.is_in_check() {
+                let winner = if self.white_turn {
+                    GameResult::BlackWins
+                } else {
+                    GameResult::WhiteWins
+                };
+                Some((winner, Termination::Checkmate))
+            } else {
+                Some((GameResult::Draw, Termination::Stalemate))
+            }
+        } else if self.has_insufficient_material() {
+            Some((GameResult::Draw, Termination::InsufficientMaterial))
+        } else if self.halfmove_clock >= 150 {
+            Some((GameResult::Draw, Termination::SeventyFiveMoveRule))
+        } else if self.repetition_count() >= 5 {
+            Some((GameResult::Draw, Termination::FivefoldRepetition))
+        } else {
+            None
+        };
+    }
+
+    // A draw can be claimed after fifty moves without captures or pawn moves, or when the
+    // current position has been reached three times
+    pub fn can_claim_draw(&self) -> bool {
+        !self.game_done() && (self.halfmove_clock >= 100 || self.repetition_count() >= 3)
+    }
+
+    pub fn claim_draw(&mut self) -> bool {
+        if !self.can_claim_draw() {
+            return false;
+        }
+        let termination = if self.repetition_count() >= 3 {
+            Termination::ThreefoldRepetition
+        } else {
+            Termination::FiftyMoveRule
+        };
+        self.outcome = Some((GameResult::Draw, termination));
+        true
+    }
+
+    // Number of times the current position has been reached
+    pub fn repetition_count(&self) -> usize {
+        let Some(&key) = self.position_history.last() else {
+            return 0;
+        };
+        // Captures and pawn moves cannot be undone, so older positions cannot come back
+        self.position_history
+            .iter()
+            .rev()
+            .take(self.halfmove_clock as usize + 1)
+            .filter(|&&position| position == key)
+            .count()
+    }
+
+    // Positions are the same when the pieces, the side to move, the castling rights and the
+    // possible en passant captures are the same, so the en passant file only counts when a
+    // pawn can actually take on it
+    pub fn zobrist_key(&self) -> u64 {
+        let mut key = self.board.zobrist_key
+            ^ zobrist::side_key(self.white_turn)
+            ^ zobrist::castling_key(self.castling_rights);
+        if let Some(target) = self.en_passant {
+            let index = position_helper::position_byte_to_index(target);
+            if self.board.bitboards.pawn_attacks(self.white_turn, index)
+                && self.legal_moves().iter().any(|m| m.is_en_passant())
+            {
+                key ^= zobrist::en_passant_key(position_helper::get_col(target));
+            }
+        }
+        key
+    }
+
+    // Neither side can mate: bare kings, a single minor piece, or only bishops all standing on
+    // squares of the same colour
+    pub fn has_insufficient_material(&self) -> bool {
+        let mut minor_pieces = Vec::new();
+        for (&position, &binary) in self.board.pieces.iter() {
+            match Piece::init_from_binary(binary).class {
+                PieceType::King => {}
+                PieceType::Knight | PieceType::Bishop => minor_pieces.push(position),
+                PieceType::Pawn | PieceType::Rook | PieceType::Queen => return false,
+            }
+        }
+
+        if minor_pieces.len() <= 1 {
+            return true;
+        }
+
+        let square_colour = |position: u8| {
+            (position_helper::get_row(position) + position_helper::get_col(position)) % 2
+        };
+        let first_colour = square_colour(minor_pieces[0]);
+        minor_pieces.iter().all(|&position| {
+            self.board.piece_at(position).unwrap().class == PieceType::Bishop
+                && square_colour(position) == first_colour
+        })
+    }
+
+    pub fn to_fen(&self) -> String {
+        fen::format(
+            &self.board.state,
+            self.white_turn,
+            self.castling_rights,
+            self.en_passant,
+            self.halfmove_clock,
+            self.fullmove_number,
+        )
+    }
+
+    pub fn is_in_check(&self) -> bool {
+        self.board
+            .king_position(self.white_turn)
+            .is_some_and(|king| self.board.is_square_attacked(king, !self.white_turn))
+    }
+
+    // Every move the side to move can legally play, each promotion piece being a different move
+    pub fn legal_moves(&self) -> Vec<Move> {
+        self.board
+            .bitboards
+            .legal_moves(self.white_turn, self.castling_rights, self.en_passant)
+    }
+
+    // Moves of the piece on the given position that do not leave its own king in check
+    fn legal_moves_from(&self, position: u8) -> Vec<Move> {
+        let mut moves = self.legal_moves();
+        moves.retain(|chess_move| chess_move.initial_position() == position);
+        moves
+    }
+
+    fn is_own_piece_square(&self, square: &str) -> bool {
+        position_helper::parse_square(square)
+            .and_then(|position| self.board.piece_at(position))
+            .is_some_and(|piece| piece.is_white == self.white_turn)
+    }
+
+    // Whether moving the piece on initial_position to final_position would promote a pawn
+    fn is_promotion(&self, initial_position: u8, final_position: u8) -> bool {
+        let final_row = position_helper::get_row(final_position);
+        self.board.piece_at(initial_position).is_some_and(|piece| {
+            piece.class == PieceType::Pawn
+                && piece.is_white == self.white_turn
+                && (final_row == 0 || final_row == 7)
+        })
+    }
+
+    pub fn board(&self) -> &Board {
+        &self.board
+    }
+
+    pub fn moves_done(&self) -> &[Move] {
+        &self.moves_done
+    }
+
+    pub fn start_fen(&self) -> &str {
+        &self.start_fen
+    }
+
+    pub fn white_turn(&self) -> bool {
+        self.white_turn
+    }
+
+    pub fn castling_rights(&self) -> u8 {
+        self.castling_rights
+    }
+
+    pub fn en_passant(&self) -> Option<u8> {
+        self.en_passant
+    }
+
+    pub fn halfmove_clock(&self) -> u32 {
+        self.halfmove_clock
+    }
+
+    pub fn fullmove_number(&self) -> u32 {
+        self.fullmove_number
+    }
+
+    pub fn play_against_computer(&mut self, computer_white: bool, depth: u32) {
+        self.play_in_terminal(Some((computer_white, depth)));
+    }
+
+    // Plays in the terminal, against another human or against the computer when it is given
+    // as its colour and the depth it searches to
+    fn play_in_terminal(&mut self, computer: Option<(bool, u32)>) {
+        let mut engine = Engine::new();
+        while !self.game_done() {
             let mut i_position_string = String::new();
             let mut f_position_string = String::new();
 
             self.board.show();
+            // Who is better, in pawns, positive when white is
+            println!(
+                "Evaluation: {:+.2}",
+                self.board.evaluate(true) as f64 / 100.0
+            );
+            println!("{}", self.to_fen());
 
             if self.white_turn {
                 println!("WHITE TURN");
             } else {
                 println!("BLACK TURN");
             }
+            if let Some((computer_white, depth)) = computer {
+                if self.white_turn == computer_white {
+                    engine.set_game(self.clone());
+                    let limits = SearchLimits {
+                        depth: Some(depth),
+                        ..SearchLimits::default()
+                    };
+                    if let Some(chess_move) = engine.search(&limits).best_move {
+                        println!("Computer plays {}", chess_move.to_san(self));
+                        self.make_move(chess_move);
+                    }
+                    continue;
+                }
+            }
+
+            if self.can_claim_draw() {
+                println!("Type draw to claim a draw");
+            }
 
-            println!("Piece initial pos: ");
-            io::stdin()
+            println!("Move (e.g. Nf3, or the square of the piece to move): ");
+            let read = io::stdin()
                 .read_line(&mut i_position_string)
                 .expect("Failed to read line");
+            // End of input, nobody is left to play
+            if read == 0 {
+                return;
+            }
 
             i_position_string = i_position_string.trim().to_string();
+            if i_position_string == "draw" {
+                if !self.claim_draw() {
+                    println!("A draw cannot be claimed yet");
+                }
+                continue;
+            }
+            if i_position_string == "undo" {
+                if self.undo_move().is_none() {
+                    println!("There is no move to undo");
+                }
+                // The move of the computer goes too, so that it is our turn again
+                if computer.is_some_and(|(computer_white, _)| self.white_turn == computer_white) {
+                    self.undo_move();
+                }
+                continue;
+            }
+
+            // A square holding one of our pieces asks for the destination in a second prompt,
+            // anything else is read as a coordinate move ("e2e4") or in algebraic notation
+            if !self.is_own_piece_square(&i_position_string) {
+                let chess_move = if looks_like_uci(&i_position_string) {
+                    Move::from_uci(self, &i_position_string).map_err(|error| error.to_string())
+                } else {
+                    Move::from_san(self, &i_position_string).map_err(|error| error.to_string())
+                };
+                match chess_move {
+                    Ok(chess_move) => {
+                        self.make_move(chess_move);
+                    }
+                    Err(error) => println!("{}", error),
+                }
+                continue;
+            }
             let i_position = position_helper::letter_to_position_byte(i_position_string);
 
             println!("Move: ");
-            io::stdin()
+            let read = io::stdin()
                 .read_line(&mut f_position_string)
                 .expect("Failed to read line");
+            if read == 0 {
+                return;
+            }
 
             f_position_string = f_position_string.trim().to_string();
-            let f_position = position_helper::letter_to_position_byte(f_position_string);
-            let move_was_valid = self.play_move(i_position, f_position);
-
-            //End of turn
-            if move_was_valid {
-                self.white_turn = !self.white_turn;
+            // A trailing letter picks the promotion piece, e.g. "e8q"
+            let mut promotion = None;
+            if f_position_string.len() == 3 {
+                promotion = f_position_string
+                    .pop()
+                    .and_then(PieceType::from_promotion_char);
             }
+            let Some(f_position) = position_helper::parse_square(&f_position_string) else {
+                println!("{} is not a square", f_position_string);
+                continue;
+            };
+
+            if promotion.is_none() && self.is_promotion(i_position, f_position) {
+                while promotion.is_none() {
+                    let mut promotion_string = String::new();
+                    println!("Promote to (q/r/b/n): ");
+                    let read = io::stdin()
+                        .read_line(&mut promotion_string)
+                        .expect("Failed to read line");
+                    if read == 0 {
+                        return;
+                    }
+                    promotion = promotion_string
+                        .trim()
+                        .chars()
+                        .next()
+                        .and_then(PieceType::from_promotion_char);
+                }
+            }
+
+            self.play_move_with_promotion(
+                i_position,
+                f_position,
+                promotion.unwrap_or(PieceType::Queen),
+            );
         }
+
+        self.board.show();
+        let winner = match self.result() {
+            Some(GameResult::WhiteWins) => "white wins",
+            Some(GameResult::BlackWins) => "black wins",
+            _ => "draw",
+        };
+        println!("{:?}, {}", self.termination().unwrap(), winner);
+        println!();
+        print!("{}", self.to_pgn());
+    }
+}
+
+impl ChessGame for Game {
+    fn play(&mut self) {
+        self.play_in_terminal(None);
     }
 
     fn play_move(&mut self, initial_position: u8, final_position: u8) -> bool {
+        // Pawns reaching the last row become queens unless asked otherwise
+        self.play_move_with_promotion(initial_position, final_position, PieceType::Queen)
+    }
+
+    fn play_move_with_promotion(
+        &mut self,
+        initial_position: u8,
+        final_position: u8,
+        promotion: PieceType,
+    ) -> bool {
+        if self.game_done() {
+            println!("The game is over");
+            return false;
+        }
+
         let piece_opt = self.board.pieces.get(&initial_position);
 
         if let Some(piece_bits) = piece_opt {
             let piece = Piece::init_from_binary(*piece_bits);
-            let possible_moves = piece.possible_moves(initial_position, &self.board);
-            if possible_moves.contains(&final_position) {
-                // Take piece
-                let final_position_index = position_helper::position_byte_to_index(final_position);
-                let taken_piece = self.board.state.get(final_position_index as usize);
-                let t_piece = taken_piece.unwrap_or(&0u8);
-                if *t_piece != 0 {
-                    //TODO: store the piece taken and give rewards
-                    if Piece::init_from_binary(*taken_piece.unwrap()).class == PieceType::King {
-                        self.game_done = true;
-                        println!("GG wp");
-                    }
+            if piece.is_white != self.white_turn {
+                println!("It is not this piece's turn");
+                return false;
+            }
+            // Promotions are only legal with a promotion piece, other moves only without one
+            let chess_move = self
+                .legal_moves_from(initial_position)
+                .into_iter()
+                .filter(|chess_move| chess_move.final_position() == final_position)
+                .find(|chess_move| {
+                    chess_move
+                        .promotion()
+                        .is_none_or(|piece| piece == promotion)
+                });
+            match chess_move {
+                Some(chess_move) => return self.make_move(chess_move),
+                None if self.is_promotion(initial_position, final_position) => {
+                    println!("A pawn cannot be promoted to a {:?}", promotion);
                 }
-                // update the board
-                self.board.state[final_position_index as usize] = piece.binary;
-                self.board.state[position_helper::position_byte_to_index(initial_position)] = 0;
-
-                self.board.update_hashmap();
-                return true;
-            } else {
-                println!("This move is not valid");
+                None => println!("This move is not valid"),
             }
         }
-        return false;
+        false
+    }
+
+    fn make_move(&mut self, chess_move: Move) -> bool {
+        if self.game_done() {
+            println!("The game is over");
+            return false;
+        }
+
+        let initial_position = chess_move.initial_position();
+        if !self
+            .legal_moves_from(initial_position)
+            .contains(&chess_move)
+        {
+            println!("This move is not valid");
+            return false;
+        }
+
+        self.apply_move(chess_move);
+        self.update_outcome();
+        true
+    }
+
+    fn undo_move(&mut self) -> Option<Move> {
+        let chess_move = self.moves_done.pop()?;
+        let undo = self
+            .undo_history
+            .pop()
+            .expect("every played move has its undo information");
+
+        self.board.unmake_move(chess_move, undo.taken_piece);
+        self.castling_rights = undo.castling_rights;
+        self.en_passant = undo.en_passant;
+        self.halfmove_clock = undo.halfmove_clock;
+        self.white_turn = !self.white_turn;
+        if !self.white_turn {
+            self.fullmove_number -= 1;
+        }
+        self.position_history.pop();
+        // Moves are only played in running games, so the game was not over before this one
+        self.outcome = None;
+        Some(chess_move)
+    }
+}
+
+// State that a move overwrites and that cannot be worked out from the move itself
+#[derive(Debug, Clone)]
+struct UndoInfo {
+    taken_piece: u8,
+    castling_rights: u8,
+    en_passant: Option<u8>,
+    halfmove_clock: u32,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GameResult {
+    WhiteWins,
+    BlackWins,
+    Draw,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Termination {
+    Checkmate,
+    Stalemate,
+    InsufficientMaterial,
+    FiftyMoveRule,
+    SeventyFiveMoveRule,
+    ThreefoldRepetition,
+    FivefoldRepetition,
+}
+
+// Two squares and maybe a promotion letter, e.g. "e7e8q"
+fn looks_like_uci(text: &str) -> bool {
+    text.is_ascii()
+        && (text.len() == 4 || text.len() == 5)
+        && position_helper::parse_square(&text[0..2]).is_some()
+        && position_helper::parse_square(&text[2..4]).is_some()
+}
+
+// The square of the pawn taken by an en passant move, beside the initial position
+fn en_passant_capture(chess_move: Move) -> u8 {
+    (chess_move.initial_position() & 0b11110000)
+        | position_helper::get_col(chess_move.final_position())
+}
+
+// Where the rook of a castling move comes from and goes to
+fn castling_rook(chess_move: Move) -> (u8, u8) {
+    let row = chess_move.initial_position() & 0b11110000;
+    if chess_move.flags() == Move::KING_CASTLE {
+        (row | 7, row | 5)
+    } else {
+        (row, row | 3)
+    }
+}
+
+// Castling rights that disappear once a piece moves from or to the given square
+fn castling_rights_lost(position: u8) -> u8 {
+    match position {
+        0x74 => CASTLE_WHITE_KINGSIDE | CASTLE_WHITE_QUEENSIDE, // e1
+        0x77 => CASTLE_WHITE_KINGSIDE,                          // h1
+        0x70 => CASTLE_WHITE_QUEENSIDE,                         // a1
+        0x04 => CASTLE_BLACK_KINGSIDE | CASTLE_BLACK_QUEENSIDE, // e8
+        0x07 => CASTLE_BLACK_KINGSIDE,                          // h8
+        0x00 => CASTLE_BLACK_QUEENSIDE,                         // a8
+        _ => 0,
     }
 }
 
@@ -114,8 +689,8 @@ pub struct Piece {
     class: PieceType,
 }
 
-#[derive(Debug, Clone, PartialEq)]
-enum PieceType {
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PieceType {
     Pawn,
     Rook,
     Knight,
@@ -124,266 +699,176 @@ enum PieceType {
     King,
 }
 
-impl Piece {
-    fn pawn_moves(self, position: u8, board: Board) -> Vec<u8> {
-        let mut possible_positions = Vec::new();
-
-        // White pawns move in the negative direction
-        if self.is_white {
-            possible_positions.push(position - 16);
-
-            if position_helper::get_row(position) == 6 {
-                possible_positions.push(position - 32);
-            }
-        }
-        // Black paws move in the positive direction
-        else {
-            possible_positions.push(position + 16);
-            if position_helper::get_row(position) == 1 {
-                possible_positions.push(position + 32);
-            }
-        }
-        //Handle taking pieces
-        if self.is_white {
-            let diagonal_right = position - ROW + COL;
-            let diagonal_left = position - ROW - COL;
-            if board.pieces.get(&diagonal_left.clone()).is_some() {
-                possible_positions.push(diagonal_left.clone());
-            }
-            if board.pieces.get(&diagonal_right.clone()).is_some() {
-                possible_positions.push(diagonal_right.clone());
-            }
+impl PieceType {
+    pub fn from_promotion_char(c: char) -> Option<PieceType> {
+        match c.to_ascii_lowercase() {
+            'q' => Some(PieceType::Queen),
+            'r' => Some(PieceType::Rook),
+            'b' => Some(PieceType::Bishop),
+            'n' => Some(PieceType::Knight),
+            _ => None,
         }
-
-        let mut final_positions = Vec::new();
-        for pos in possible_positions {
-            if position_helper::validate_position(pos) {
-                final_positions.push(pos);
-            }
-        }
-
-        final_positions
     }
+}
 
-    fn king_moves(self, position: u8, _board: Board) -> Vec<u8> {
-        let possible_positions = vec![
-            position + COL,
-            position - COL,
-            position + ROW,
-            position - ROW,
-            position + ROW + COL,
-            position + ROW - COL,
-            position - ROW + COL,
-            position - ROW - COL,
-        ];
-
-        let mut final_positions: Vec<u8> = Vec::new();
-        for pos in possible_positions {
-            if position_helper::validate_position(pos) {
-                final_positions.push(pos);
-            }
-        }
-
-        final_positions
+impl Piece {
+    // A promoted pawn keeps its index (the lower three bits) so that its key stays unique,
+    // the promotion bits then tell which piece it became
+    fn promoted_binary(&self, promotion: &PieceType) -> Option<u8> {
+        let promotion_bits = match promotion {
+            PieceType::Queen => 0u8,
+            PieceType::Rook => 8u8,
+            PieceType::Bishop => 16u8,
+            PieceType::Knight => 24u8,
+            PieceType::Pawn | PieceType::King => return None,
+        };
+        let colour_bit = self.binary & WHITE_BIT;
+        Some(
+            PIECE_BIT
+                + colour_bit
+                + PROMOTED_BIT
+                + promotion_bits
+                + (self.binary & PAWN_INDEX_MASK),
+        )
     }
 
-    fn rook_moves(self, position: u8, board: Board) -> Vec<u8> {
-        let mut possible_positions = Vec::<u8>::new();
-        let row = position_helper::get_row(position);
-        let col = position_helper::get_col(position);
+    fn pawn_moves(&self, position: u8, board: &Board) -> Vec<u8> {
+        let mut possible_positions = Vec::new();
 
-        let mut blocked_right: bool = false;
-        let mut blocked_up: bool = false;
-        let mut blocked_down: bool = false;
-        let mut blocked_left: bool = false;
-        // move up, down, left, and right from the current position
-        // check that there is no piece in the way
-        for i in 1..8 {
-            if col + i < 8 && !blocked_right {
-                // check right boundary
-                let position_to_check = position + i;
-                let piece_retrieved = board.pieces.get(&position_to_check);
+        // White pawns move in the negative direction, black pawns in the positive one
+        let (forward, start_row) = if self.is_white { (-1, 6) } else { (1, 1) };
 
-                // If a piece is found, we are now blocked from moving forward
-                blocked_right = piece_retrieved.is_some();
-                possible_positions.push(position + i);
-            }
-            if i <= col && !blocked_left {
-                // check left boundary
-                let position_to_check = position - i;
-                let piece_retrieved = board.pieces.get(&position_to_check);
-
-                // If a piece is found, we are now blocked from moving forward
-                blocked_left = piece_retrieved.is_some();
-                possible_positions.push(position - i);
-            }
-            if row + i < 8 && !blocked_down {
-                // check lower boundary
-                let position_to_check = position + ROW * i;
-                let piece_retrieved = board.pieces.get(&position_to_check);
-
-                // If a piece is found, we are now blocked from moving forward
-                blocked_down = piece_retrieved.is_some();
-                possible_positions.push(position + ROW * i);
-            }
-            if i <= row && !blocked_up {
-                // check upper boundary
-                let position_to_check = position - ROW * i;
-                let piece_retrieved = board.pieces.get(&position_to_check);
+        // Pushes need empty squares, the double push also needs the skipped square empty
+        if let Some(one_step) = position_helper::offset(position, forward, 0) {
+            if !board.pieces.contains_key(&one_step) {
+                possible_positions.push(one_step);
 
-                blocked_up = piece_retrieved.is_some();
-                possible_positions.push(position - ROW * i);
+                let two_steps = position_helper::offset(position, 2 * forward, 0);
+                if let Some(two_steps) = two_steps {
+                    if position_helper::get_row(position) == start_row
+                        && !board.pieces.contains_key(&two_steps)
+                    {
+                        possible_positions.push(two_steps);
+                    }
+                }
             }
         }
 
-        let mut final_positions = Vec::new();
-        for pos in possible_positions {
-            if position_helper::is_position_valid(pos, &board, self.is_white) {
-                final_positions.push(pos);
+        //Handle taking pieces, en passant is added by the Game which knows the last move
+        for d_col in [-1, 1] {
+            if let Some(diagonal) = position_helper::offset(position, forward, d_col) {
+                let takes_opponent = board
+                    .pieces
+                    .get(&diagonal)
+                    .is_some_and(|binary| ((binary & WHITE_BIT) == WHITE_BIT) != self.is_white);
+                if takes_opponent {
+                    possible_positions.push(diagonal);
+                }
             }
         }
 
-        return final_positions;
+        possible_positions
+    }
+
+    fn king_moves(&self, position: u8, board: &Board) -> Vec<u8> {
+        self.leaper_moves(position, board, &KING_DIRECTIONS)
     }
 
-    fn queen_moves(self, position: u8, board: Board) -> Vec<u8> {
-        let row = position_helper::get_row(position);
-        let col = position_helper::get_col(position);
+    fn rook_moves(&self, position: u8, board: &Board) -> Vec<u8> {
+        self.sliding_moves(position, board, &ROOK_DIRECTIONS)
+    }
 
-        let mut queen_positions = self.clone().rook_moves(position, board.clone());
+    fn queen_moves(&self, position: u8, board: &Board) -> Vec<u8> {
+        let mut queen_positions = self.rook_moves(position, board);
         let mut bishop_positions = self.bishop_moves(position, board);
 
         queen_positions.append(&mut bishop_positions);
-        return queen_positions.to_vec();
-
+        queen_positions
     }
 
-    fn bishop_moves(self, position: u8, board: Board) -> Vec<u8> {
-        let row = position_helper::get_row(position);
-        let col = position_helper::get_col(position);
-        let mut blocked_up_left = false;
-        let mut blocked_down_left = false;
-        let mut blocked_up_right = false;
-        let mut blocked_down_right = false;
-
-        (1..8)
-            .filter_map(|i| {
-                let mut moves = Vec::new();
-
-                if col + i < 8 {
-                    if row + i < 8 && !blocked_down_right {
-                        let position_to_check = position + i + ROW * i;
-                        let piece_retrieved = board.pieces.get(&position_to_check);
-
-                        blocked_down_right = piece_retrieved.is_some();
-                        moves.push(position + i + ROW * i);
-                    }
-                    if i <= row && !blocked_up_right {
-                        let position_to_check = position + i - ROW * i;
-                        let piece_retrieved = board.pieces.get(&position_to_check);
-
-                        blocked_up_right = piece_retrieved.is_some();
-                        moves.push(position + i - ROW * i);
-                    }
-                }
-
-                if i <= col {
-                    if row + i < 8 && !blocked_down_left {
-                        let position_to_check = position - i + ROW * i;
-                        let piece_retrieved = board.pieces.get(&position_to_check);
-
-                        blocked_down_left= piece_retrieved.is_some();
-                        moves.push(position - i + ROW * i);
-                    }
-                    if i <= row && !blocked_up_left{
-                        let position_to_check = position - i - ROW * i;
-                        let piece_retrieved = board.pieces.get(&position_to_check);
+    fn bishop_moves(&self, position: u8, board: &Board) -> Vec<u8> {
+        self.sliding_moves(position, board, &BISHOP_DIRECTIONS)
+    }
 
-                        blocked_up_left = piece_retrieved.is_some();
-                        moves.push(position - i - ROW * i);
-                    }
-                }
+    fn knight_moves(&self, position: u8, board: &Board) -> Vec<u8> {
+        self.leaper_moves(position, board, &KNIGHT_DIRECTIONS)
+    }
 
-                Some(moves)
-            })
-            .flatten()
-            .filter(|&pos| position_helper::is_position_valid(pos, &board, self.is_white))
+    // One step in each direction, the 0x88 test catches the steps leaving the board
+    fn leaper_moves(&self, position: u8, board: &Board, directions: &[(i8, i8)]) -> Vec<u8> {
+        directions
+            .iter()
+            .map(|&(d_row, d_col)| position.wrapping_add(position_helper::step(d_row, d_col)))
+            .filter(|&pos| position_helper::is_position_valid(pos, board, self.is_white))
             .collect()
     }
 
-    fn knight_moves(self, position: u8, board: Board) -> Vec<u8> {
-        let possible_positions: Vec<u8> = vec![
-            position
-                .checked_add(COL)
-                .and_then(|x| x.checked_add(2 * ROW)),
-            position
-                .checked_sub(COL)
-                .and_then(|x| x.checked_add(2 * ROW)),
-            position
-                .checked_add(COL)
-                .and_then(|x| x.checked_sub(2 * ROW)),
-            position
-                .checked_sub(COL)
-                .and_then(|x| x.checked_sub(2 * ROW)),
-            position
-                .checked_add(ROW)
-                .and_then(|x| x.checked_add(2 * COL)),
-            position
-                .checked_add(ROW)
-                .and_then(|x| x.checked_sub(2 * COL)),
-            position
-                .checked_sub(ROW)
-                .and_then(|x| x.checked_add(2 * COL)),
-            position
-                .checked_sub(ROW)
-                .and_then(|x| x.checked_sub(2 * COL)),
-        ]
-        .into_iter()
-        .flatten()
-        .collect();
-
-        let mut final_positions = Vec::new();
-        for pos in possible_positions {
-            if position_helper::validate_position(pos) {
-                final_positions.push(pos);
+    // Walks each direction until the edge of the board or a piece, which can be taken if it is
+    // an opponent's
+    fn sliding_moves(&self, position: u8, board: &Board, directions: &[(i8, i8)]) -> Vec<u8> {
+        let mut possible_positions = Vec::new();
+        for &(d_row, d_col) in directions {
+            let step = position_helper::step(d_row, d_col);
+            let mut current = position.wrapping_add(step);
+            while position_helper::is_on_board(current) {
+                match board.pieces.get(&current) {
+                    None => possible_positions.push(current),
+                    Some(binary) => {
+                        if ((binary & WHITE_BIT) == WHITE_BIT) != self.is_white {
+                            possible_positions.push(current);
+                        }
+                        break;
+                    }
+                }
+                current = current.wrapping_add(step);
             }
         }
-
-        return final_positions;
+        possible_positions
     }
 }
 
 impl BasicPiece for Piece {
     fn is_move_valid(&self, position: u8, board: Board) -> bool {
-        //TODO: implement this
+        /*
+        Checks whether this piece can move to the given position without leaving its king in check.
+        The piece is found on the board through its unique key. Castling and en passant depend on
+        the previous moves, so only the Game can validate those.
+        */
+        let Some(initial_position) = board
+            .pieces
+            .iter()
+            .find(|(_, &binary)| binary == self.binary)
+            .map(|(&initial_position, _)| initial_position)
+        else {
+            return false;
+        };
 
-        return true;
+        if !self
+            .possible_moves(initial_position, &board)
+            .contains(&position)
+        {
+            return false;
+        }
+
+        let flags = if board.pieces.contains_key(&position) {
+            Move::CAPTURE
+        } else {
+            Move::QUIET
+        };
+        let mut board = board;
+        board.make_move(Move::new(initial_position, position, flags));
+        !board.leaves_king_attacked(self.is_white)
     }
 
     fn possible_moves(&self, position: u8, board: &Board) -> Vec<u8> {
-        let mut possible_positions = Vec::new();
         match self.class {
-            PieceType::Pawn => {
-                possible_positions = Piece::pawn_moves(self.clone(), position, board.clone())
-            }
-            PieceType::King => {
-                possible_positions = Piece::king_moves(self.clone(), position, board.clone())
-            }
-            PieceType::Bishop => {
-                possible_positions = Piece::bishop_moves(self.clone(), position, board.clone())
-            }
-            PieceType::Queen => {
-                possible_positions = Piece::queen_moves(self.clone(), position, board.clone())
-            }
-            PieceType::Rook => {
-                possible_positions = Piece::rook_moves(self.clone(), position, board.clone())
-            }
-            PieceType::Knight => {
-                possible_positions = Piece::knight_moves(self.clone(), position, board.clone())
-            }
+            PieceType::Pawn => self.pawn_moves(position, board),
+            PieceType::King => self.king_moves(position, board),
+            PieceType::Bishop => self.bishop_moves(position, board),
+            PieceType::Queen => self.queen_moves(position, board),
+            PieceType::Rook => self.rook_moves(position, board),
+            PieceType::Knight => self.knight_moves(position, board),
         }
-
-        possible_positions
     }
 
     fn init_from_binary(binary: u8) -> Self {
@@ -391,14 +876,23 @@ impl BasicPiece for Piece {
         // The alive bit might mess this up
         let binary_piece = binary & CHECK_PIECE;
 
-        let piece_type = match binary_piece {
-            8u8..=16u8 => PieceType::Pawn,
-            0u8 => PieceType::King,
-            1u8 => PieceType::Queen,
-            2u8 | 3u8 => PieceType::Bishop,
-            4u8 | 5u8 => PieceType::Knight,
-            6u8 | 7u8 => PieceType::Rook,
-            _ => panic!("This piece does not exist!. The binary is {}", binary),
+        let piece_type = if binary & PROMOTED_BIT == PROMOTED_BIT {
+            match binary & PROMOTION_MASK {
+                0u8 => PieceType::Queen,
+                8u8 => PieceType::Rook,
+                16u8 => PieceType::Bishop,
+                _ => PieceType::Knight,
+            }
+        } else {
+            match binary_piece {
+                8u8..=16u8 => PieceType::Pawn,
+                0u8 => PieceType::King,
+                1u8 => PieceType::Queen,
+                2u8 | 3u8 => PieceType::Bishop,
+                4u8 | 5u8 => PieceType::Knight,
+                6u8 | 7u8 => PieceType::Rook,
+                _ => panic!("This piece does not exist!. The binary is {}", binary),
+            }
         };
 
         Self {
@@ -410,13 +904,11 @@ impl BasicPiece for Piece {
 
     fn text_repr(&self) -> String {
         let mut return_string = String::from("");
-        let mut color_string = String::from("");
-
-        if self.is_white {
-            color_string = String::from("w");
+        let color_string = if self.is_white {
+            String::from("w")
         } else {
-            color_string = String::from("b");
-        }
+            String::from("b")
+        };
 
         let piece_string = match self.class {
             PieceType::Pawn => "p".to_string(),
@@ -443,6 +935,8 @@ pub trait BasicPiece {
 pub struct Board {
     pub pieces: HashMap<u8, u8>, // HashMap<positionByte, pieceByte>
     pub state: [u8; 64],         // arr[index] = pieceByte
+    bitboards: Bitboards,        // used for move generation
+    zobrist_key: u64,            // of the pieces only, the Game adds the rest
 }
 
 impl Board {
@@ -475,20 +969,17 @@ impl Board {
 
     pub fn init() -> Self {
         let mut state = [0u8; 64];
-        let pieces: HashMap<u8, u8> = HashMap::new();
 
         // black pawns
-        let mut first_bpawn = PIECE_BIT + PAWN_BIT;
+        let first_bpawn = PIECE_BIT + PAWN_BIT;
         for i in 0..8 {
-            state[i + 8] = first_bpawn;
-            first_bpawn += 1;
+            state[i + 8] = first_bpawn + i as u8;
         }
 
         // white pawns
-        let mut first_wpawn = PIECE_BIT + PAWN_BIT + WHITE_BIT;
+        let first_wpawn = PIECE_BIT + PAWN_BIT + WHITE_BIT;
         for i in 0..8 {
-            state[i + 48] = first_wpawn;
-            first_wpawn += 1;
+            state[i + 48] = first_wpawn + i as u8;
         }
 
         // white large pieces
@@ -511,17 +1002,210 @@ impl Board {
         state[6] = KNIGHT + PIECE_BIT + 1;
         state[7] = ROOK + PIECE_BIT + 1;
 
-        // Populate hashmap -> done in the update_hashmap
-        Self { pieces, state }
+        let mut board = Self {
+            pieces: HashMap::new(),
+            state,
+            bitboards: Bitboards::default(),
+            zobrist_key: 0,
+        };
+        board.update_hashmap();
+        board
+    }
+
+    // A board without any piece, to place pieces on with set_piece
+    pub fn empty() -> Self {
+        Self {
+            pieces: HashMap::new(),
+            state: [0u8; 64],
+            bitboards: Bitboards::default(),
+            zobrist_key: 0,
+        }
+    }
+
+    pub fn from_fen(fen: &str) -> Result<Self, FenError> {
+        // Only the piece placement is kept, the rest of the fields belong to the Game.
+        // A bare placement field (as produced by Board::to_fen) is accepted as well.
+        let state = if fen.split_whitespace().count() == 1 {
+            fen::parse_placement(fen.trim())?
+        } else {
+            fen::parse(fen)?.state
+        };
+        let mut board = Self {
+            pieces: HashMap::new(),
+            state,
+            bitboards: Bitboards::default(),
+            zobrist_key: 0,
+        };
+        board.update_hashmap();
+        Ok(board)
+    }
+
+    pub fn to_fen(&self) -> String {
+        fen::format_placement(&self.state)
+    }
+
+    fn piece_at(&self, position: u8) -> Option<Piece> {
+        self.pieces
+            .get(&position)
+            .map(|binary| Piece::init_from_binary(*binary))
+    }
+
+    pub fn bitboards(&self) -> &Bitboards {
+        &self.bitboards
+    }
+
+    pub fn zobrist_key(&self) -> u64 {
+        self.zobrist_key
+    }
+
+    pub fn king_position(&self, is_white: bool) -> Option<u8> {
+        self.bitboards
+            .king_index(is_white)
+            .map(position_helper::index_to_position_byte)
+    }
+
+    pub fn is_square_attacked(&self, position: u8, by_white: bool) -> bool {
+        self.bitboards
+            .is_attacked(position_helper::position_byte_to_index(position), by_white)
+    }
+
+    // Plays a move on the board, including the rook of a castle, the pawn taken en passant and
+    // the piece a pawn is promoted to. The move is not checked, use Game::legal_moves for that.
+    // Returns the piece byte that was taken, 0 when nothing was taken.
+    pub fn make_move(&mut self, chess_move: Move) -> u8 {
+        let initial_position = chess_move.initial_position();
+        let final_position = chess_move.final_position();
+
+        let moving_piece = Piece::init_from_binary(self.remove_piece(initial_position));
+        let moved_binary = chess_move
+            .promotion()
+            .and_then(|promotion| moving_piece.promoted_binary(&promotion))
+            .unwrap_or(moving_piece.binary);
+
+        // En passant: the captured pawn sits beside the initial position, not on the final one
+        let taken_piece = if chess_move.is_en_passant() {
+            self.remove_piece(en_passant_capture(chess_move))
+        } else {
+            self.remove_piece(final_position)
+        };
+        self.set_piece(final_position, moved_binary);
+
+        // Castling: the rook jumps to the other side of the king
+        if chess_move.is_castle() {
+            let (rook_from, rook_to) = castling_rook(chess_move);
+            let rook = self.remove_piece(rook_from);
+            self.set_piece(rook_to, rook);
+        }
+
+        self.debug_check_consistency();
+        taken_piece
+    }
+
+    // Takes back a move played with make_move, taken_piece being the value it returned
+    pub fn unmake_move(&mut self, chess_move: Move, taken_piece: u8) {
+        let initial_position = chess_move.initial_position();
+        let final_position = chess_move.final_position();
+
+        // A promoted piece kept the index of its pawn, so the pawn can be rebuilt from it
+        let moved_binary = self.remove_piece(final_position);
+        let original_binary = if chess_move.is_promotion() {
+            PIECE_BIT + (moved_binary & WHITE_BIT) + PAWN_BIT + (moved_binary & PAWN_INDEX_MASK)
+        } else {
+            moved_binary
+        };
+        self.set_piece(initial_position, original_binary);
+
+        if chess_move.is_en_passant() {
+            self.set_piece(en_passant_capture(chess_move), taken_piece);
+        } else {
+            self.set_piece(final_position, taken_piece);
+        }
+
+        if chess_move.is_castle() {
+            let (rook_from, rook_to) = castling_rook(chess_move);
+            let rook = self.remove_piece(rook_to);
+            self.set_piece(rook_from, rook);
+        }
+
+        self.debug_check_consistency();
+    }
+
+    // Puts a piece byte on an empty square, keeping pieces and the bitboards in line with state.
+    // Setting 0 does nothing so that an empty "taken piece" can be put back as is.
+    pub fn set_piece(&mut self, position: u8, binary: u8) {
+        if binary == 0 {
+            return;
+        }
+        let index = position_helper::position_byte_to_index(position);
+        debug_assert_eq!(self.state[index], 0, "set_piece on a taken square");
+        self.state[index] = binary;
+        self.pieces.insert(position, binary);
+        self.bitboards.toggle_piece(binary, index);
+        self.zobrist_key ^= zobrist::piece_key(binary, index);
+    }
+
+    // Empties a square and returns the piece byte that was on it, 0 when it was already empty
+    pub fn remove_piece(&mut self, position: u8) -> u8 {
+        let index = position_helper::position_byte_to_index(position);
+        let binary = self.state[index];
+        if binary != 0 {
+            self.state[index] = 0;
+            self.pieces.remove(&position);
+            self.bitboards.toggle_piece(binary, index);
+            self.zobrist_key ^= zobrist::piece_key(binary, index);
+        }
+        binary
+    }
+
+    // Debug builds check after every move that pieces and the bitboards agree with state
+    fn debug_check_consistency(&self) {
+        if !cfg!(debug_assertions) {
+            return;
+        }
+        for (index, &binary) in self.state.iter().enumerate() {
+            let position = position_helper::index_to_position_byte(index);
+            let stored = self.pieces.get(&position).copied().unwrap_or(0);
+            assert_eq!(
+                stored,
+                binary,
+                "pieces and state disagree on {}",
+                position_helper::position_byte_to_letter(position)
+            );
+        }
+        assert_eq!(
+            self.pieces.len(),
+            self.state.iter().filter(|&&binary| binary != 0).count(),
+            "pieces has entries outside of the board"
+        );
+        assert_eq!(
+            self.bitboards,
+            Bitboards::from_state(&self.state),
+            "bitboards and state disagree"
+        );
+        assert_eq!(
+            self.zobrist_key,
+            zobrist::placement_key(&self.state),
+            "zobrist key and state disagree"
+        );
     }
 
+    fn leaves_king_attacked(&self, is_white: bool) -> bool {
+        self.king_position(is_white)
+            .is_some_and(|king| self.is_square_attacked(king, !is_white))
+    }
+
+    // Rebuilds pieces, the bitboards and the zobrist key from state, only needed after editing state directly
     pub fn update_hashmap(&mut self) {
+        // Start from scratch so that vacated squares do not keep their old piece
+        self.pieces.clear();
         for index in 0..self.state.len() {
             if self.state[index] != 0 {
                 let pos_byte = position_helper::index_to_position_byte(index);
                 self.pieces.insert(pos_byte, self.state[index]);
             }
         }
+        self.bitboards = Bitboards::from_state(&self.state);
+        self.zobrist_key = zobrist::placement_key(&self.state);
     }
 }
 
@@ -571,6 +1255,40 @@ pub mod position_helper {
         (row << 4) | col
     }
 
+    /*
+    The position byte is laid out as a 0x88 board: rows and columns only need 3 of the 4 bits
+    of their nibble, and any step that leaves the board sets the highest bit of one of them,
+    wrapping around below row or column 0 included. A single mask check is then enough to
+    know whether a square is on the board.
+    */
+    pub const OFF_BOARD: u8 = 0x88;
+
+    pub const fn is_on_board(position: u8) -> bool {
+        position & OFF_BOARD == 0
+    }
+
+    // The amount to add (wrapping) to a position byte to move by the given rows and columns
+    pub const fn step(d_row: i8, d_col: i8) -> u8 {
+        (d_row as i16 * 16 + d_col as i16) as u8
+    }
+
+    // Like letter_to_position_byte, but returns None when the text is not a square
+    pub fn parse_square(square: &str) -> Option<u8> {
+        let mut chars = square.chars();
+        let (Some(letter), Some(number), None) = (chars.next(), chars.next(), chars.next()) else {
+            return None;
+        };
+        if !('a'..='h').contains(&letter) || !('1'..='8').contains(&number) {
+            return None;
+        }
+        Some(letter_to_position_byte(square.to_string()))
+    }
+
+    pub fn offset(position: u8, d_row: i8, d_col: i8) -> Option<u8> {
+        let destination = position.wrapping_add(step(d_row, d_col));
+        is_on_board(destination).then_some(destination)
+    }
+
     pub fn get_row(byte: u8) -> u8 {
         let row_selector: u8 = 0b11110000;
         (row_selector & byte) >> 4
@@ -582,18 +1300,7 @@ pub mod position_helper {
     }
 
     pub fn validate_position(position: u8) -> bool {
-        let index_position = position_byte_to_index(position);
-        if index_position >= 64 {
-            return false;
-        }
-        if get_col(position) > 7 {
-            return false;
-        }
-        if get_row(position) > 7 {
-            return false;
-        }
-
-        true
+        is_on_board(position)
     }
 
     pub fn is_position_valid(
@@ -604,7 +1311,7 @@ pub mod position_helper {
         /*
         Checks whether position is within bounds and whether there is a same-coloured piece in the position
         */
-        if self::validate_position(destination_position) == false {
+        if !self::validate_position(destination_position) {
             return false;
         }
 
@@ -616,19 +1323,6 @@ pub mod position_helper {
         let piece_byte = piece_opt.unwrap();
         let is_white = (piece_byte & WHITE_BIT) == WHITE_BIT;
 
-        if is_white == is_piece_white {
-            return false;
-        }
-
-        return true;
+        is_white != is_piece_white
     }
 }
-
-pub mod cherris_engine {
-    use crate::Game;
-
-    pub struct Engine {
-        game: Game,
-        
-    }
-}
\ No newline at end of file
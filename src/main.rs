@@ -1,17 +1,31 @@
-use color_eyre::eyre::Result;
 use cherris::{self, ChessGame};
+use color_eyre::eyre::{eyre, Result};
+use std::env;
 
+const DEFAULT_COMPUTER_DEPTH: u32 = 4;
 
 // Instructions:
 /*
--  The position of a piece is given by byte, the first four bits represent the row, counting from top to bottom, the columns from left to right 
--  Each piece has a unique key that is represented by a byte. That key follows the following structure. 
-    - The first bit represents whether the piece exists or not 
+-  The position of a piece is given by byte, the first four bits represent the row, counting from top to bottom, the columns from left to right
+-  Each piece has a unique key that is represented by a byte. That key follows the following structure.
+    - The first bit represents whether the piece exists or not
     - The second bit indicates whether a piece is white or black (white=1, black=0)
     - The last four bits represent the pieces (numbers 0-15) as below
+    - A promoted pawn sets the third bit, keeps its pawn number (0-7) in the last three bits and
+      stores the new piece in the two bits in between (queen=0, rook=1, bishop=2, knight=3)
 */
-fn main() -> Result<()>{
+fn main() -> Result<()> {
     color_eyre::install()?;
+
+    // cherris perft <depth> [fen] | cherris divide <depth> [fen]
+    // cherris play <white|black> [depth]
+    let args: Vec<String> = env::args().skip(1).collect();
+    match args.first().map(String::as_str) {
+        Some("play") => return run_play(&args[1..]),
+        Some(command) => return run_perft(command, &args[1..]),
+        None => {}
+    }
+
     let mut board: cherris::Board = cherris::Board::init();
     board.update_hashmap();
 
@@ -22,3 +36,52 @@ fn main() -> Result<()>{
     Ok(())
 }
 
+// Plays against the computer, with the colour we play and how deep the computer looks
+fn run_play(args: &[String]) -> Result<()> {
+    let usage = || eyre!("usage: play <white|black> [depth]");
+    let computer_white = match args.first().map(String::as_str) {
+        Some("white") => false,
+        Some("black") => true,
+        _ => return Err(usage()),
+    };
+    let depth = match args.get(1) {
+        Some(depth) => depth.parse().map_err(|_| usage())?,
+        None => DEFAULT_COMPUTER_DEPTH,
+    };
+
+    let mut game = cherris::Game::init();
+    game.play_against_computer(computer_white, depth);
+    Ok(())
+}
+
+fn run_perft(command: &str, args: &[String]) -> Result<()> {
+    let depth: u32 = args
+        .first()
+        .ok_or_else(|| eyre!("usage: {} <depth> [fen]", command))?
+        .parse()?;
+    let fen = if args.len() > 1 {
+        args[1..].join(" ")
+    } else {
+        String::from(cherris::STARTING_FEN)
+    };
+    let mut game = cherris::Game::from_fen(&fen)?;
+
+    match command {
+        "perft" => println!("{}", game.perft(depth)),
+        "divide" => {
+            let divide = game.perft_divide(depth);
+            for (chess_move, nodes) in &divide {
+                println!("{}: {}", chess_move, nodes);
+            }
+            let total: u64 = divide.iter().map(|(_, nodes)| nodes).sum();
+            println!("\nMoves: {}\nNodes: {}", divide.len(), total);
+        }
+        _ => {
+            return Err(eyre!(
+                "unknown command '{}', expected perft or divide",
+                command
+            ))
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,190 @@
+use std::fmt;
+
+use crate::{position_helper, Game, PieceType};
+
+/*
+A move packed in 16 bits:
+    - bits 0-5: index (0-63) of the initial position, as in Board::state
+    - bits 6-11: index of the final position
+    - bits 12-15: flags telling what kind of move it is
+
+The flags follow the usual layout where the highest bit marks promotions and the next one
+captures, so that both can be tested with a single mask:
+    0000 quiet move                 1000 promotion to knight
+    0001 double pawn push           1001 promotion to bishop
+    0010 kingside castle            1010 promotion to rook
+    0011 queenside castle           1011 promotion to queen
+    0100 capture                    1100-1111 promotion with capture (same piece order)
+    0101 en passant capture
+*/
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Move(u16);
+
+const INDEX_MASK: u16 = 0b111111;
+const FINAL_SHIFT: u16 = 6;
+const FLAGS_SHIFT: u16 = 12;
+
+impl Move {
+    pub const QUIET: u8 = 0b0000;
+    pub const DOUBLE_PAWN_PUSH: u8 = 0b0001;
+    pub const KING_CASTLE: u8 = 0b0010;
+    pub const QUEEN_CASTLE: u8 = 0b0011;
+    pub const CAPTURE: u8 = 0b0100;
+    pub const EN_PASSANT: u8 = 0b0101;
+    pub const PROMOTION: u8 = 0b1000;
+
+    pub fn new(initial_position: u8, final_position: u8, flags: u8) -> Move {
+        let initial_index = position_helper::position_byte_to_index(initial_position) as u16;
+        let final_index = position_helper::position_byte_to_index(final_position) as u16;
+        Move(
+            initial_index | (final_index << FINAL_SHIFT) | ((flags as u16 & 0b1111) << FLAGS_SHIFT),
+        )
+    }
+
+    pub fn new_promotion(
+        initial_position: u8,
+        final_position: u8,
+        promotion: PieceType,
+        is_capture: bool,
+    ) -> Option<Move> {
+        let piece_bits = match promotion {
+            PieceType::Knight => 0,
+            PieceType::Bishop => 1,
+            PieceType::Rook => 2,
+            PieceType::Queen => 3,
+            PieceType::Pawn | PieceType::King => return None,
+        };
+        let capture_bit = if is_capture { Move::CAPTURE } else { 0 };
+        Some(Move::new(
+            initial_position,
+            final_position,
+            Move::PROMOTION | capture_bit | piece_bits,
+        ))
+    }
+
+    pub fn from_u16(packed: u16) -> Move {
+        Move(packed)
+    }
+
+    pub fn to_u16(self) -> u16 {
+        self.0
+    }
+
+    pub fn initial_position(self) -> u8 {
+        position_helper::index_to_position_byte((self.0 & INDEX_MASK) as usize)
+    }
+
+    pub fn final_position(self) -> u8 {
+        position_helper::index_to_position_byte(((self.0 >> FINAL_SHIFT) & INDEX_MASK) as usize)
+    }
+
+    pub fn flags(self) -> u8 {
+        (self.0 >> FLAGS_SHIFT) as u8
+    }
+
+    pub fn is_capture(self) -> bool {
+        self.flags() & Move::CAPTURE != 0
+    }
+
+    pub fn is_promotion(self) -> bool {
+        self.flags() & Move::PROMOTION != 0
+    }
+
+    pub fn is_en_passant(self) -> bool {
+        self.flags() == Move::EN_PASSANT
+    }
+
+    pub fn is_double_pawn_push(self) -> bool {
+        self.flags() == Move::DOUBLE_PAWN_PUSH
+    }
+
+    pub fn is_castle(self) -> bool {
+        self.flags() == Move::KING_CASTLE || self.flags() == Move::QUEEN_CASTLE
+    }
+
+    // Reads a move in the coordinate notation of the UCI protocol, e.g. "e2e4", "e1g1" for
+    // castling or "e7e8q", and finds it among the legal moves of the game
+    pub fn from_uci(game: &Game, uci: &str) -> Result<Move, UciMoveError> {
+        let uci = uci.trim();
+        let syntax_error = || UciMoveError::Syntax(uci.to_string());
+        if !uci.is_ascii() || (uci.len() != 4 && uci.len() != 5) {
+            return Err(syntax_error());
+        }
+        let initial_position =
+            position_helper::parse_square(&uci[0..2]).ok_or_else(syntax_error)?;
+        let final_position = position_helper::parse_square(&uci[2..4]).ok_or_else(syntax_error)?;
+        let promotion = match uci.chars().nth(4) {
+            Some(c) => Some(
+                PieceType::from_promotion_char(c)
+                    .filter(|_| c.is_ascii_lowercase())
+                    .ok_or_else(syntax_error)?,
+            ),
+            None => None,
+        };
+
+        game.legal_moves()
+            .into_iter()
+            .find(|chess_move| {
+                chess_move.initial_position() == initial_position
+                    && chess_move.final_position() == final_position
+                    && chess_move.promotion() == promotion
+            })
+            .ok_or_else(|| UciMoveError::IllegalMove(uci.to_string()))
+    }
+
+    pub fn to_uci(self) -> String {
+        self.to_string()
+    }
+
+    pub fn promotion(self) -> Option<PieceType> {
+        if !self.is_promotion() {
+            return None;
+        }
+        match self.flags() & 0b11 {
+            0 => Some(PieceType::Knight),
+            1 => Some(PieceType::Bishop),
+            2 => Some(PieceType::Rook),
+            _ => Some(PieceType::Queen),
+        }
+    }
+}
+
+/// Error returned when a coordinate move such as "e2e4" cannot be read or is not legal.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum UciMoveError {
+    Syntax(String),
+    IllegalMove(String),
+}
+
+impl fmt::Display for UciMoveError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            UciMoveError::Syntax(uci) => write!(f, "'{}' is not a coordinate move", uci),
+            UciMoveError::IllegalMove(uci) => write!(f, "{} is not a legal move", uci),
+        }
+    }
+}
+
+impl std::error::Error for UciMoveError {}
+
+// Coordinate notation, e.g. "e2e4" or "e7e8q"
+impl fmt::Display for Move {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}{}",
+            position_helper::position_byte_to_letter(self.initial_position()),
+            position_helper::position_byte_to_letter(self.final_position())
+        )?;
+        if let Some(promotion) = self.promotion() {
+            let c = match promotion {
+                PieceType::Knight => 'n',
+                PieceType::Bishop => 'b',
+                PieceType::Rook => 'r',
+                _ => 'q',
+            };
+            write!(f, "{}", c)?;
+        }
+        Ok(())
+    }
+}
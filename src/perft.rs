@@ -0,0 +1,51 @@
+use crate::{ChessGame, Game, Move};
+
+impl Game {
+    // Counts the leaf nodes of the move tree down to the given depth. Comparing the counts with
+    // published results is the usual way of checking a move generator.
+    pub fn perft(&mut self, depth: u32) -> u64 {
+        if depth == 0 {
+            return 1;
+        }
+        // The end of the game is not looked for while walking the tree, only the moves matter
+        let outcome = self.outcome.take();
+        let nodes = self.perft_nodes(depth);
+        self.outcome = outcome;
+        nodes
+    }
+
+    // Same as perft but gives the count below each legal move of the position, which helps
+    // finding the move where a generator goes wrong
+    pub fn perft_divide(&mut self, depth: u32) -> Vec<(Move, u64)> {
+        let outcome = self.outcome.take();
+        let mut divide = Vec::new();
+        for chess_move in self.legal_moves() {
+            self.apply_move(chess_move);
+            let nodes = if depth <= 1 {
+                1
+            } else {
+                self.perft_nodes(depth - 1)
+            };
+            self.undo_move();
+            divide.push((chess_move, nodes));
+        }
+        self.outcome = outcome;
+        divide
+    }
+
+    fn perft_nodes(&mut self, depth: u32) -> u64 {
+        let moves = self.legal_moves();
+        // The moves of the last level do not need to be played to be counted
+        if depth == 1 {
+            return moves.len() as u64;
+        }
+
+        let mut nodes = 0;
+        for chess_move in moves {
+            self.apply_move(chess_move);
+            nodes += self.perft_nodes(depth - 1);
+            self.undo_move();
+        }
+        nodes
+    }
+}
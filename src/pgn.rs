@@ -0,0 +1,397 @@
+use std::{fmt, time::Duration};
+
+use crate::{FenError, Game, GameResult, Move, SanError, STARTING_FEN};
+
+/// Error returned when a PGN text cannot be read. Errors in the moves say which move of the
+/// game failed, e.g. "12... Nf3". read_pgn gives every error inside a Line, so that the game it
+/// comes from can be found in a long file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PgnError {
+    Line {
+        line: usize,
+        error: Box<PgnError>,
+    },
+    Tag(String),
+    Unterminated(String),
+    Fen(FenError),
+    IllegalMove {
+        move_number: u32,
+        white_turn: bool,
+        san: String,
+        reason: String,
+    },
+}
+
+impl fmt::Display for PgnError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PgnError::Line { line, error } => write!(f, "line {}: {}", line, error),
+            PgnError::Tag(tag) => write!(f, "invalid tag pair {}", tag),
+            PgnError::Unterminated(what) => write!(f, "unterminated {}", what),
+            PgnError::Fen(error) => write!(f, "invalid FEN tag: {}", error),
+            PgnError::IllegalMove {
+                move_number,
+                white_turn,
+                san,
+                reason,
+            } => {
+                let dots = if *white_turn { "." } else { "..." };
+                write!(f, "move {}{} {}: {}", move_number, dots, san, reason)
+            }
+        }
+    }
+}
+
+impl std::error::Error for PgnError {}
+
+/// A game read from PGN: its tag pairs in the order they were found, the game replayed up to
+/// the last move and the result written at the end of the movetext.
+#[derive(Clone)]
+pub struct PgnGame {
+    pub tags: Vec<(String, String)>,
+    pub game: Game,
+    pub result: String,
+}
+
+impl PgnGame {
+    pub fn tag(&self, name: &str) -> Option<&str> {
+        self.tags
+            .iter()
+            .find(|(tag, _)| tag == name)
+            .map(|(_, value)| value.as_str())
+    }
+
+    pub fn to_pgn(&self) -> String {
+        let mut tags = self.tags.clone();
+        if self.game.result().is_none() {
+            set_tag(&mut tags, "Result", &self.result);
+        }
+        write_pgn(&self.game, &tags, None)
+    }
+}
+
+// Tags every PGN game must have, in this order, with the value used when it is unknown
+const SEVEN_TAG_ROSTER: [(&str, &str); 7] = [
+    ("Event", "?"),
+    ("Site", "?"),
+    ("Date", "????.??.??"),
+    ("Round", "?"),
+    ("White", "?"),
+    ("Black", "?"),
+    ("Result", "*"),
+];
+
+// PGN export lines should not be longer than this
+const MAX_LINE_LENGTH: usize = 79;
+
+impl Game {
+    pub fn to_pgn(&self) -> String {
+        write_pgn(self, &[], None)
+    }
+}
+
+/*
+Writes a game in the PGN export format. The Seven Tag Roster comes first, filled with "?" for
+the tags not given, then the other tags, then the moves in SAN from the position the game started
+from. The result is the one of the game when it is over, otherwise the Result tag or "*".
+When clocks are given, the time left after each move is added as a {[%clk 0:05:00]} comment.
+*/
+pub fn write_pgn(game: &Game, tags: &[(String, String)], clocks: Option<&[Duration]>) -> String {
+    let mut tags = tags.to_vec();
+    if let Some(result) = game.result() {
+        let result = match result {
+            GameResult::WhiteWins => "1-0",
+            GameResult::BlackWins => "0-1",
+            GameResult::Draw => "1/2-1/2",
+        };
+        set_tag(&mut tags, "Result", result);
+    }
+    if game.start_fen() != STARTING_FEN {
+        set_tag(&mut tags, "SetUp", "1");
+        set_tag(&mut tags, "FEN", game.start_fen());
+    }
+
+    let mut pgn = String::new();
+    let tag_value = |name: &str| tags.iter().find(|(tag, _)| tag == name).map(|(_, v)| v);
+    for (name, default) in SEVEN_TAG_ROSTER {
+        let value = tag_value(name).map_or(default, |value| value.as_str());
+        pgn.push_str(&format_tag(name, value));
+    }
+    for (name, value) in &tags {
+        if !SEVEN_TAG_ROSTER.iter().any(|(roster, _)| roster == name) {
+            pgn.push_str(&format_tag(name, value));
+        }
+    }
+    pgn.push('\n');
+
+    let mut words = Vec::new();
+    let mut replay = Game::from_fen(game.start_fen()).expect("the start FEN was valid");
+    // A black move needs its number again after a comment, as after the first move
+    let mut after_comment = true;
+    for (i, &chess_move) in game.moves_done().iter().enumerate() {
+        if replay.white_turn() {
+            words.push(format!("{}.", replay.fullmove_number()));
+        } else if after_comment {
+            words.push(format!("{}...", replay.fullmove_number()));
+        }
+        words.push(chess_move.to_san(&replay));
+        after_comment = false;
+        if let Some(clock) = clocks.and_then(|clocks| clocks.get(i)) {
+            words.push(format_clock(*clock));
+            after_comment = true;
+        }
+        replay.apply_move(chess_move);
+    }
+    words.push(
+        tag_value("Result")
+            .map_or("*", |value| value.as_str())
+            .to_string(),
+    );
+
+    let mut line_length = 0;
+    for word in words {
+        if line_length > 0 && line_length + 1 + word.len() > MAX_LINE_LENGTH {
+            pgn.push('\n');
+            line_length = 0;
+        } else if line_length > 0 {
+            pgn.push(' ');
+            line_length += 1;
+        }
+        line_length += word.len();
+        pgn.push_str(&word);
+    }
+    pgn.push('\n');
+    pgn
+}
+
+fn set_tag(tags: &mut Vec<(String, String)>, name: &str, value: &str) {
+    match tags.iter_mut().find(|(tag, _)| tag == name) {
+        Some((_, old)) => *old = value.to_string(),
+        None => tags.push((name.to_string(), value.to_string())),
+    }
+}
+
+fn format_tag(name: &str, value: &str) -> String {
+    let value = value.replace('\\', "\\\\").replace('"', "\\\"");
+    format!("[{} \"{}\"]\n", name, value)
+}
+
+fn format_clock(clock: Duration) -> String {
+    let seconds = clock.as_secs();
+    format!(
+        "{{[%clk {}:{:02}:{:02}]}}",
+        seconds / 3600,
+        seconds / 60 % 60,
+        seconds % 60
+    )
+}
+
+// What is left of the text once comments, variations, NAGs and move numbers are taken out
+#[derive(Debug, PartialEq)]
+enum Token {
+    Tag(String, String),
+    San(String),
+    Result(String),
+}
+
+// Reads every game of a PGN text
+pub fn read_pgn(pgn: &str) -> Result<Vec<PgnGame>, PgnError> {
+    let mut games = Vec::new();
+    let mut tokens = tokenize(pgn)?.into_iter().peekable();
+
+    while let Some(&(game_line, _)) = tokens.peek() {
+        let mut tags = Vec::new();
+        while let Some((_, Token::Tag(..))) = tokens.peek() {
+            if let Some((_, Token::Tag(name, value))) = tokens.next() {
+                tags.push((name, value));
+            }
+        }
+
+        // Games that do not start from the usual position give it in the FEN tag
+        let fen = tags
+            .iter()
+            .find(|(name, _)| name == "FEN")
+            .map_or(STARTING_FEN, |(_, value)| value.as_str());
+        let mut game =
+            Game::from_fen(fen).map_err(|error| at_line(game_line, PgnError::Fen(error)))?;
+
+        let mut result = String::from("*");
+        for (line, token) in tokens.by_ref() {
+            match token {
+                Token::San(san) => {
+                    play_san(&mut game, &san).map_err(|error| at_line(line, error))?
+                }
+                Token::Result(game_result) => {
+                    result = game_result;
+                    break;
+                }
+                Token::Tag(name, value) => {
+                    let tag = format!("[{} \"{}\"] inside the moves", name, value);
+                    return Err(at_line(line, PgnError::Tag(tag)));
+                }
+            }
+        }
+        game.update_outcome();
+
+        games.push(PgnGame { tags, game, result });
+    }
+
+    Ok(games)
+}
+
+fn play_san(game: &mut Game, san: &str) -> Result<(), PgnError> {
+    let illegal = |game: &Game, reason: String| PgnError::IllegalMove {
+        move_number: game.fullmove_number(),
+        white_turn: game.white_turn(),
+        san: san.to_string(),
+        reason,
+    };
+
+    let chess_move =
+        Move::from_san(game, san).map_err(|error: SanError| illegal(game, error.to_string()))?;
+    // Older games may go on past a draw that now ends the game by itself, only the end of the
+    // game is looked for once all its moves are played
+    game.apply_move(chess_move);
+    Ok(())
+}
+
+fn at_line(line: usize, error: PgnError) -> PgnError {
+    PgnError::Line {
+        line,
+        error: Box::new(error),
+    }
+}
+
+// Every token comes with the line it starts on, as do the errors
+fn tokenize(pgn: &str) -> Result<Vec<(usize, Token)>, PgnError> {
+    let mut tokens = Vec::new();
+    let mut chars = pgn.chars().peekable();
+    let mut line = 1;
+    let mut line_start = true;
+
+    while let Some(c) = chars.next() {
+        let start = line;
+        let unterminated = |what: &str| at_line(start, PgnError::Unterminated(what.to_string()));
+        match c {
+            '\n' => {
+                line += 1;
+                line_start = true;
+                continue;
+            }
+            // Escaped lines and ';' comments run to the end of the line
+            '%' if line_start => skip_line(&mut chars),
+            ';' => skip_line(&mut chars),
+            '{' => {
+                if !skip_comment(&mut chars, &mut line) {
+                    return Err(unterminated("comment"));
+                }
+            }
+            // Variations are skipped, only the main line is played
+            '(' => {
+                let mut depth = 1;
+                while depth > 0 {
+                    match chars.next() {
+                        Some('(') => depth += 1,
+                        Some(')') => depth -= 1,
+                        Some('{') => {
+                            if !skip_comment(&mut chars, &mut line) {
+                                return Err(unterminated("comment"));
+                            }
+                        }
+                        Some('\n') => line += 1,
+                        Some(_) => {}
+                        None => return Err(unterminated("variation")),
+                    }
+                }
+            }
+            '[' => {
+                let tag = read_tag(&mut chars).ok_or_else(|| unterminated("tag pair"))?;
+                let tag = parse_tag(&tag).map_err(|error| at_line(start, error))?;
+                tokens.push((start, tag));
+            }
+            c if c.is_whitespace() => {}
+            c => {
+                let mut word = String::from(c);
+                while let Some(&next) = chars.peek() {
+                    if next.is_whitespace() || "{}()[];".contains(next) {
+                        break;
+                    }
+                    word.push(next);
+                    chars.next();
+                }
+                tokens.extend(word_token(&word).map(|token| (start, token)));
+            }
+        }
+        line_start = false;
+    }
+
+    Ok(tokens)
+}
+
+fn skip_line(chars: &mut std::iter::Peekable<std::str::Chars>) {
+    while chars.next_if(|&c| c != '\n').is_some() {}
+}
+
+// Skips to the end of a comment, false when it never ends
+fn skip_comment(chars: &mut std::iter::Peekable<std::str::Chars>, line: &mut usize) -> bool {
+    for c in chars.by_ref() {
+        match c {
+            '}' => return true,
+            '\n' => *line += 1,
+            _ => {}
+        }
+    }
+    false
+}
+
+// What is between the brackets of a tag pair, on a single line. The value may hold brackets and
+// escaped quotes, the pair only ends on a bracket outside of it.
+fn read_tag(chars: &mut std::iter::Peekable<std::str::Chars>) -> Option<String> {
+    let mut tag = String::new();
+    let mut quoted = false;
+    while let Some(c) = chars.next_if(|&c| c != '\n') {
+        match c {
+            ']' if !quoted => return Some(tag),
+            '"' => quoted = !quoted,
+            '\\' if quoted => {
+                tag.push(c);
+                tag.push(chars.next_if(|&c| c != '\n')?);
+                continue;
+            }
+            _ => {}
+        }
+        tag.push(c);
+    }
+    None
+}
+
+fn parse_tag(tag: &str) -> Result<Token, PgnError> {
+    let invalid = || PgnError::Tag(format!("[{}]", tag));
+    let (name, value) = tag
+        .trim()
+        .split_once(char::is_whitespace)
+        .ok_or_else(invalid)?;
+    let value = value.trim();
+    if name.is_empty() || value.len() < 2 || !value.starts_with('"') || !value.ends_with('"') {
+        return Err(invalid());
+    }
+    let value = value[1..value.len() - 1]
+        .replace("\\\"", "\"")
+        .replace("\\\\", "\\");
+    Ok(Token::Tag(name.to_string(), value))
+}
+
+// A word of the movetext: a move, possibly glued to its number ("12.e4"), a result, a NAG
+// ("$1") or a bare move number
+fn word_token(word: &str) -> Option<Token> {
+    if ["1-0", "0-1", "1/2-1/2", "*"].contains(&word) {
+        return Some(Token::Result(word.to_string()));
+    }
+    if word.starts_with('$') {
+        return None;
+    }
+    let san = word.trim_start_matches(|c: char| c.is_ascii_digit() || c == '.');
+    // "0-0" would lose its zeros above, it never follows a move number without a space
+    let san = if word.starts_with("0-0") { word } else { san };
+    (!san.is_empty()).then(|| Token::San(san.to_string()))
+}
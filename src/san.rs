@@ -0,0 +1,249 @@
+use std::fmt;
+
+use crate::{position_helper, BasicPiece, Game, Move, Piece, PieceType};
+
+/// Error returned when a move in Standard Algebraic Notation cannot be read or does not match
+/// exactly one legal move of the position.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SanError {
+    Syntax(String),
+    IllegalMove(String),
+    AmbiguousMove(String),
+}
+
+impl fmt::Display for SanError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SanError::Syntax(san) => write!(f, "'{}' is not a move in algebraic notation", san),
+            SanError::IllegalMove(san) => write!(f, "{} is not a legal move", san),
+            SanError::AmbiguousMove(san) => {
+                write!(f, "{} is ambiguous, add the file or rank of the piece", san)
+            }
+        }
+    }
+}
+
+impl std::error::Error for SanError {}
+
+// The fields of a SAN move other than castling, e.g. "Nbd7" or "exd8=Q+"
+struct SanParts {
+    class: PieceType,
+    from_col: Option<u8>,
+    from_row: Option<u8>,
+    destination: u8,
+    promotion: Option<PieceType>,
+}
+
+impl Move {
+    // Reads a move such as "e4", "Nbd7", "exd5", "O-O-O" or "e8=Q+" in the position of the game.
+    // Check and mate marks and annotations like "!?" are accepted but not checked.
+    pub fn from_san(game: &Game, san: &str) -> Result<Move, SanError> {
+        let trimmed = san.trim().trim_end_matches(['+', '#', '!', '?']);
+        let syntax_error = || SanError::Syntax(san.trim().to_string());
+
+        let legal_moves = game.legal_moves();
+        if let Some(flags) = castling_flags(trimmed) {
+            return legal_moves
+                .into_iter()
+                .find(|chess_move| chess_move.flags() == flags)
+                .ok_or_else(|| SanError::IllegalMove(trimmed.to_string()));
+        }
+
+        let parts = parse_parts(trimmed).ok_or_else(syntax_error)?;
+        let mut candidates = legal_moves.into_iter().filter(|&chess_move| {
+            let initial_position = chess_move.initial_position();
+            piece_class(game, initial_position) == Some(parts.class)
+                && chess_move.final_position() == parts.destination
+                && chess_move.promotion() == parts.promotion
+                && parts
+                    .from_col
+                    .is_none_or(|col| position_helper::get_col(initial_position) == col)
+                && parts
+                    .from_row
+                    .is_none_or(|row| position_helper::get_row(initial_position) == row)
+        });
+
+        match (candidates.next(), candidates.next()) {
+            (Some(chess_move), None) => Ok(chess_move),
+            (Some(_), Some(_)) => Err(SanError::AmbiguousMove(trimmed.to_string())),
+            (None, _) => Err(SanError::IllegalMove(trimmed.to_string())),
+        }
+    }
+
+    // Writes the move in Standard Algebraic Notation. The move must be legal in the position
+    // of the game, which is the position before the move is played.
+    pub fn to_san(self, game: &Game) -> String {
+        let mut san = match self.flags() {
+            Move::KING_CASTLE => String::from("O-O"),
+            Move::QUEEN_CASTLE => String::from("O-O-O"),
+            _ => self.piece_san(game),
+        };
+
+        let mut after = game.clone();
+        after.apply_move(self);
+        if after.is_in_check() {
+            san.push(if after.legal_moves().is_empty() {
+                '#'
+            } else {
+                '+'
+            });
+        }
+        san
+    }
+
+    fn piece_san(self, game: &Game) -> String {
+        let initial_position = self.initial_position();
+        let final_position = self.final_position();
+        let class = piece_class(game, initial_position).unwrap_or(PieceType::Pawn);
+        let mut san = String::new();
+
+        if class == PieceType::Pawn {
+            // Pawn captures name the file the pawn comes from
+            if self.is_capture() {
+                san.push(file_char(initial_position));
+            }
+        } else {
+            san.push(piece_char(class));
+
+            // Other pieces of the same kind that can go to the same square
+            let others: Vec<u8> = game
+                .legal_moves()
+                .into_iter()
+                .filter(|other| {
+                    other.final_position() == final_position
+                        && other.initial_position() != initial_position
+                        && piece_class(game, other.initial_position()) == Some(class)
+                })
+                .map(|other| other.initial_position())
+                .collect();
+            if !others.is_empty() {
+                let same_col = |position: &u8| {
+                    position_helper::get_col(*position)
+                        == position_helper::get_col(initial_position)
+                };
+                let same_row = |position: &u8| {
+                    position_helper::get_row(*position)
+                        == position_helper::get_row(initial_position)
+                };
+                if !others.iter().any(same_col) {
+                    san.push(file_char(initial_position));
+                } else if !others.iter().any(same_row) {
+                    san.push(rank_char(initial_position));
+                } else {
+                    san.push(file_char(initial_position));
+                    san.push(rank_char(initial_position));
+                }
+            }
+        }
+
+        if self.is_capture() {
+            san.push('x');
+        }
+        san.push_str(&position_helper::position_byte_to_letter(final_position));
+        if let Some(promotion) = self.promotion() {
+            san.push('=');
+            san.push(piece_char(promotion));
+        }
+        san
+    }
+}
+
+fn castling_flags(san: &str) -> Option<u8> {
+    match san {
+        "O-O" | "0-0" => Some(Move::KING_CASTLE),
+        "O-O-O" | "0-0-0" => Some(Move::QUEEN_CASTLE),
+        _ => None,
+    }
+}
+
+fn parse_parts(san: &str) -> Option<SanParts> {
+    let mut chars: Vec<char> = san.chars().collect();
+
+    let class = piece_from_char(*chars.first()?);
+    if class.is_some() {
+        chars.remove(0);
+    }
+    let class = class.unwrap_or(PieceType::Pawn);
+
+    // "=Q" or a bare "Q" after the square
+    let mut promotion = None;
+    if let Some(&last) = chars.last() {
+        if let Some(piece) = piece_from_char(last) {
+            if class != PieceType::Pawn || piece == PieceType::King {
+                return None;
+            }
+            promotion = Some(piece);
+            chars.pop();
+            if chars.last() == Some(&'=') {
+                chars.pop();
+            }
+        }
+    }
+
+    if chars.len() < 2 {
+        return None;
+    }
+    let square: String = chars[chars.len() - 2..].iter().collect();
+    let destination = position_helper::parse_square(&square)?;
+    chars.truncate(chars.len() - 2);
+    if chars.last() == Some(&'x') {
+        chars.pop();
+    }
+
+    // What is left tells the file and/or rank of the piece
+    let (mut from_col, mut from_row) = (None, None);
+    for c in chars {
+        match c {
+            'a'..='h' if from_col.is_none() && from_row.is_none() => {
+                from_col = Some(c as u8 - b'a')
+            }
+            '1'..='8' if from_row.is_none() => from_row = Some(b'8' - c as u8),
+            _ => return None,
+        }
+    }
+
+    Some(SanParts {
+        class,
+        from_col,
+        from_row,
+        destination,
+        promotion,
+    })
+}
+
+fn piece_class(game: &Game, position: u8) -> Option<PieceType> {
+    game.board()
+        .pieces
+        .get(&position)
+        .map(|&binary| Piece::init_from_binary(binary).class)
+}
+
+fn piece_from_char(c: char) -> Option<PieceType> {
+    match c {
+        'N' => Some(PieceType::Knight),
+        'B' => Some(PieceType::Bishop),
+        'R' => Some(PieceType::Rook),
+        'Q' => Some(PieceType::Queen),
+        'K' => Some(PieceType::King),
+        _ => None,
+    }
+}
+
+fn piece_char(class: PieceType) -> char {
+    match class {
+        PieceType::Knight => 'N',
+        PieceType::Bishop => 'B',
+        PieceType::Rook => 'R',
+        PieceType::Queen => 'Q',
+        PieceType::King => 'K',
+        PieceType::Pawn => 'P',
+    }
+}
+
+fn file_char(position: u8) -> char {
+    (b'a' + position_helper::get_col(position)) as char
+}
+
+fn rank_char(position: u8) -> char {
+    (b'8' - position_helper::get_row(position)) as char
+}
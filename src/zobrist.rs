@@ -0,0 +1,76 @@
+use crate::{BasicPiece, Piece, PieceType};
+
+/*
+Zobrist hashing: every (colour, piece kind, square) has a random 64 bit key, as well as the side
+to move, each castling right and each en passant file. The key of a position is the xor of the
+keys of everything in it, so a move only needs to xor in and out what it changes.
+
+The keys are generated at compile time with splitmix64 from a fixed seed, so they are the same
+in every build and hashes can be stored in databases.
+    - 0..768: pieces, indexed by colour (black 0, white 1), kind and square index
+    - 768: white to move
+    - 769..773: castling rights, in the order of the CASTLE_* bits
+    - 773..781: en passant file
+*/
+const PIECE_KEYS: usize = 0;
+const SIDE_KEY: usize = 768;
+const CASTLING_KEYS: usize = 769;
+const EN_PASSANT_KEYS: usize = 773;
+
+const KEYS: [u64; 781] = generate_keys(0x2545_f491_4f6c_dd1d);
+
+const fn generate_keys(seed: u64) -> [u64; 781] {
+    let mut keys = [0u64; 781];
+    let mut state = seed;
+    let mut i = 0;
+    while i < keys.len() {
+        state = state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        keys[i] = z ^ (z >> 31);
+        i += 1;
+    }
+    keys
+}
+
+// Key of a piece byte standing on the square with the given index of Board::state
+pub(crate) fn piece_key(binary: u8, index: usize) -> u64 {
+    let piece = Piece::init_from_binary(binary);
+    let kind = match piece.class {
+        PieceType::Pawn => 0,
+        PieceType::Knight => 1,
+        PieceType::Bishop => 2,
+        PieceType::Rook => 3,
+        PieceType::Queen => 4,
+        PieceType::King => 5,
+    };
+    KEYS[PIECE_KEYS + (piece.is_white as usize * 6 + kind) * 64 + index]
+}
+
+pub(crate) fn side_key(white_turn: bool) -> u64 {
+    if white_turn {
+        KEYS[SIDE_KEY]
+    } else {
+        0
+    }
+}
+
+pub(crate) fn castling_key(castling_rights: u8) -> u64 {
+    (0..4)
+        .filter(|bit| castling_rights & (1 << bit) != 0)
+        .fold(0, |key, bit| key ^ KEYS[CASTLING_KEYS + bit])
+}
+
+pub(crate) fn en_passant_key(col: u8) -> u64 {
+    KEYS[EN_PASSANT_KEYS + col as usize]
+}
+
+// Key of the pieces alone, computed from scratch
+pub(crate) fn placement_key(state: &[u8; 64]) -> u64 {
+    state
+        .iter()
+        .enumerate()
+        .filter(|(_, &binary)| binary != 0)
+        .fold(0, |key, (index, &binary)| key ^ piece_key(binary, index))
+}
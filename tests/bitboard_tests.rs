@@ -0,0 +1,77 @@
+use cherris::*;
+
+const PIECE_BIT: u8 = 128u8;
+const WHITE_BIT: u8 = 64u8;
+const KNIGHT: u8 = 4u8;
+const ROOK: u8 = 6u8;
+
+fn bit(s: &str) -> u64 {
+    1 << position_helper::position_byte_to_index(position_helper::letter_to_position_byte(
+        String::from(s),
+    ))
+}
+
+#[test]
+fn test_bitboards_follow_state() {
+    let mut game = Game::init();
+    let bitboards = *game.board().bitboards();
+    assert_eq!(bitboards.occupied().count_ones(), 32);
+    assert_eq!(bitboards.colour(true), 0xffff << 48);
+    assert_eq!(bitboards.colour(false), 0xffff);
+    assert_eq!(
+        bitboards.pieces(true, PieceType::Knight),
+        bit("b1") | bit("g1")
+    );
+    assert_eq!(bitboards.pieces(false, PieceType::King), bit("e8"));
+
+    let square = |s: &str| position_helper::letter_to_position_byte(String::from(s));
+    assert!(game.play_move(square("e2"), square("e4")));
+    let bitboards = game.board().bitboards();
+    assert_eq!(
+        bitboards.pieces(true, PieceType::Pawn) & bit("e4"),
+        bit("e4")
+    );
+    assert_eq!(bitboards.occupied() & bit("e2"), 0);
+    assert_eq!(*bitboards, Bitboards::from_state(&game.board().state));
+}
+
+#[test]
+fn test_bitboard_attacks() {
+    let board = Board::from_fen("4k3/8/8/3p4/8/1B6/8/R3K3").unwrap();
+    let square = |s: &str| position_helper::letter_to_position_byte(String::from(s));
+
+    // the rook is stopped by nothing on the first rank but by its own king
+    assert!(board.is_square_attacked(square("d1"), true));
+    assert!(!board.is_square_attacked(square("g1"), true));
+    assert!(board.is_square_attacked(square("a8"), true));
+    // the bishop sees up to the pawn, not behind it
+    assert!(board.is_square_attacked(square("d5"), true));
+    assert!(!board.is_square_attacked(square("e6"), true));
+    // black pawns attack downwards
+    assert!(board.is_square_attacked(square("e4"), false));
+    assert!(!board.is_square_attacked(square("e6"), false));
+    assert_eq!(board.king_position(false), Some(square("e8")));
+}
+
+#[test]
+fn test_set_and_remove_piece() {
+    let square = |s: &str| position_helper::letter_to_position_byte(String::from(s));
+    let mut board = Board::empty();
+    let rook = PIECE_BIT + WHITE_BIT + ROOK;
+    let knight = PIECE_BIT + KNIGHT;
+    board.set_piece(square("a1"), rook);
+    board.set_piece(square("a4"), knight);
+    assert_eq!(board.pieces.get(&square("a4")), Some(&knight));
+    assert_eq!(board.bitboards().occupied(), bit("a1") | bit("a4"));
+
+    let rook_piece = Piece::init_from_binary(rook);
+    assert_eq!(rook_piece.possible_moves(square("a1"), &board).len(), 10);
+
+    // the vacated square no longer blocks the rook
+    assert_eq!(board.remove_piece(square("a4")), knight);
+    assert_eq!(board.remove_piece(square("a4")), 0);
+    assert!(!board.pieces.contains_key(&square("a4")));
+    assert_eq!(rook_piece.possible_moves(square("a1"), &board).len(), 14);
+    assert_eq!(board.bitboards().occupied(), bit("a1"));
+    assert_eq!(board.to_fen(), "8/8/8/8/8/8/8/R7");
+}
@@ -0,0 +1,52 @@
+mod common;
+
+use cherris::*;
+use common::square;
+
+#[test]
+fn test_castle_kingside_and_queenside() {
+    let mut game = Game::from_fen("r3k2r/8/8/8/8/8/8/R3K2R w KQkq - 0 1").unwrap();
+    assert!(game.play_move(square("e1"), square("g1")));
+    assert_eq!(game.to_fen(), "r3k2r/8/8/8/8/8/8/R4RK1 b kq - 1 1");
+
+    assert!(game.play_move(square("e8"), square("c8")));
+    assert_eq!(game.to_fen(), "2kr3r/8/8/8/8/8/8/R4RK1 w - - 2 2");
+}
+
+#[test]
+fn test_castle_not_through_or_out_of_check() {
+    // The rook on f2 covers f1, so only the queenside is available
+    let mut game = Game::from_fen("4k3/8/8/8/8/8/5r2/R3K2R w KQ - 0 1").unwrap();
+    assert!(!game.play_move(square("e1"), square("g1")));
+    assert!(game.play_move(square("e1"), square("c1")));
+
+    // The king is in check from the rook on e2
+    let mut game = Game::from_fen("4k3/8/8/8/8/8/4r3/R3K2R w KQ - 0 1").unwrap();
+    assert!(!game.play_move(square("e1"), square("g1")));
+    assert!(!game.play_move(square("e1"), square("c1")));
+}
+
+#[test]
+fn test_castle_blocked_or_without_rights() {
+    let mut game = Game::from_fen("4k3/8/8/8/8/8/8/RN2K1NR w KQ - 0 1").unwrap();
+    assert!(!game.play_move(square("e1"), square("g1")));
+    assert!(!game.play_move(square("e1"), square("c1")));
+
+    let mut game = Game::from_fen("4k3/8/8/8/8/8/8/R3K2R w - - 0 1").unwrap();
+    assert!(!game.play_move(square("e1"), square("g1")));
+}
+
+#[test]
+fn test_castling_rights_tracking() {
+    // Capturing a rook on its corner removes the rights of both sides
+    let mut game = Game::from_fen("r3k2r/8/8/8/8/8/8/R3K2R w KQkq - 0 1").unwrap();
+    assert!(game.play_move(square("a1"), square("a8")));
+    assert_eq!(
+        game.castling_rights(),
+        CASTLE_WHITE_KINGSIDE | CASTLE_BLACK_KINGSIDE
+    );
+
+    // Moving the king removes both of its rights
+    assert!(game.play_move(square("e8"), square("d7")));
+    assert_eq!(game.castling_rights(), CASTLE_WHITE_KINGSIDE);
+}
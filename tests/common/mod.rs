@@ -0,0 +1,6 @@
+use cherris::position_helper;
+
+// The position byte of a square written like "e4"
+pub fn square(letters: &str) -> u8 {
+    position_helper::parse_square(letters).unwrap()
+}
@@ -0,0 +1,95 @@
+mod common;
+
+use cherris::*;
+use common::square;
+
+fn shuffle_knights(game: &mut Game) {
+    assert!(game.play_move(square("g1"), square("f3")));
+    assert!(game.play_move(square("g8"), square("f6")));
+    assert!(game.play_move(square("f3"), square("g1")));
+    assert!(game.play_move(square("f6"), square("g8")));
+}
+
+#[test]
+fn test_threefold_and_fivefold_repetition() {
+    let mut game = Game::init();
+    assert_eq!(game.repetition_count(), 1);
+    shuffle_knights(&mut game);
+    assert!(!game.can_claim_draw());
+    shuffle_knights(&mut game);
+    assert_eq!(game.repetition_count(), 3);
+    assert!(game.can_claim_draw());
+    assert!(!game.game_done());
+
+    shuffle_knights(&mut game);
+    shuffle_knights(&mut game);
+    assert_eq!(game.result(), Some(GameResult::Draw));
+    assert_eq!(game.termination(), Some(Termination::FivefoldRepetition));
+}
+
+#[test]
+fn test_claim_threefold_repetition() {
+    let mut game = Game::init();
+    assert!(!game.claim_draw());
+    shuffle_knights(&mut game);
+    shuffle_knights(&mut game);
+    assert!(game.claim_draw());
+    assert_eq!(game.termination(), Some(Termination::ThreefoldRepetition));
+}
+
+#[test]
+fn test_en_passant_right_changes_the_position() {
+    // After d2d4 black can take en passant, so the position differs from the one reached
+    // later with the same pieces
+    let mut game = Game::from_fen("4k3/8/8/8/4p3/8/3P4/4K3 w - - 0 1").unwrap();
+    assert!(game.play_move(square("d2"), square("d4")));
+    assert_eq!(game.repetition_count(), 1);
+    assert!(game.play_move(square("e8"), square("d8")));
+    assert!(game.play_move(square("e1"), square("d1")));
+    assert!(game.play_move(square("d8"), square("e8")));
+    assert!(game.play_move(square("d1"), square("e1")));
+    assert_eq!(game.repetition_count(), 1);
+}
+
+#[test]
+fn test_fifty_and_seventy_five_move_rules() {
+    let mut game = Game::from_fen("4k3/8/8/8/8/8/8/R3K3 w - - 99 80").unwrap();
+    assert!(!game.can_claim_draw());
+    assert!(game.play_move(square("a1"), square("a2")));
+    assert!(game.can_claim_draw());
+    assert!(game.claim_draw());
+    assert_eq!(game.termination(), Some(Termination::FiftyMoveRule));
+
+    let mut game = Game::from_fen("4k3/8/8/8/8/8/8/R3K3 w - - 149 80").unwrap();
+    assert!(game.play_move(square("a1"), square("a2")));
+    assert_eq!(game.termination(), Some(Termination::SeventyFiveMoveRule));
+}
+
+#[test]
+fn test_insufficient_material() {
+    for fen in [
+        "4k3/8/8/8/8/8/8/4K3 w - - 0 1",
+        "4k3/8/8/8/8/8/8/2B1K3 w - - 0 1",
+        "4k3/8/8/8/8/8/8/1N2K3 w - - 0 1",
+        "2b1k3/8/8/8/8/8/8/3BK3 w - - 0 1",
+    ] {
+        let game = Game::from_fen(fen).unwrap();
+        assert!(game.has_insufficient_material(), "{}", fen);
+        assert_eq!(game.termination(), Some(Termination::InsufficientMaterial));
+    }
+
+    for fen in [
+        "4k3/8/8/8/8/8/8/2B1KB2 w - - 0 1",
+        "1n2k3/8/8/8/8/8/8/1N2K3 w - - 0 1",
+        "4k3/8/8/8/8/8/P7/4K3 w - - 0 1",
+    ] {
+        let game = Game::from_fen(fen).unwrap();
+        assert!(!game.has_insufficient_material(), "{}", fen);
+        assert!(!game.game_done());
+    }
+
+    // Taking the last pawn ends the game
+    let mut game = Game::from_fen("4k3/8/8/8/8/8/p7/1K6 w - - 0 1").unwrap();
+    assert!(game.play_move(square("b1"), square("a2")));
+    assert_eq!(game.termination(), Some(Termination::InsufficientMaterial));
+}
@@ -0,0 +1,68 @@
+mod common;
+
+use cherris::*;
+use common::square;
+
+#[test]
+fn test_white_captures_en_passant() {
+    let mut game = Game::init();
+    assert!(game.play_move(square("e2"), square("e4")));
+    assert_eq!(game.en_passant(), Some(square("e3")));
+    assert!(game.play_move(square("a7"), square("a6")));
+    assert_eq!(game.en_passant(), None);
+    assert!(game.play_move(square("e4"), square("e5")));
+    assert!(game.play_move(square("d7"), square("d5")));
+    assert_eq!(game.en_passant(), Some(square("d6")));
+
+    assert!(game.play_move(square("e5"), square("d6")));
+    assert!(!game.board().pieces.contains_key(&square("d5")));
+    assert_eq!(
+        game.to_fen(),
+        "rnbqkbnr/1pp1pppp/p2P4/8/8/8/PPPP1PPP/RNBQKBNR b KQkq - 0 3"
+    );
+}
+
+#[test]
+fn test_black_captures_en_passant() {
+    let mut game = Game::from_fen("4k3/8/8/8/4p3/8/3P4/4K3 w - - 0 1").unwrap();
+    assert!(game.play_move(square("d2"), square("d4")));
+    assert!(game.play_move(square("e4"), square("d3")));
+    assert_eq!(game.to_fen(), "4k3/8/8/8/8/3p4/8/4K3 w - - 0 2");
+}
+
+#[test]
+fn test_en_passant_expires() {
+    let mut game = Game::from_fen("4k3/8/8/8/4p3/8/3P4/4K3 w - - 0 1").unwrap();
+    assert!(game.play_move(square("d2"), square("d4")));
+    assert!(game.play_move(square("e8"), square("e7")));
+    assert!(game.play_move(square("e1"), square("e2")));
+    assert!(!game.play_move(square("e4"), square("d3")));
+}
+
+#[test]
+fn test_en_passant_needs_the_pushed_pawn() {
+    // Nothing stands on e5, so e6 cannot be an en passant target
+    assert!(matches!(
+        Game::from_fen("4k3/8/8/3P4/8/8/8/4K3 w - e6 0 1"),
+        Err(FenError::EnPassant(_))
+    ));
+    // A white pawn on e5 did not make the last move either
+    assert!(matches!(
+        Game::from_fen("4k3/8/8/3PP3/8/8/8/4K3 w - e6 0 1"),
+        Err(FenError::EnPassant(_))
+    ));
+
+    let game = Game::from_fen("4k3/8/8/3Pp3/8/8/8/4K3 w - e6 0 1").unwrap();
+    let moves: Vec<String> = game.legal_moves().iter().map(|m| m.to_uci()).collect();
+    assert!(moves.contains(&String::from("d5e6")));
+}
+
+#[test]
+fn test_pawn_pushes_and_captures() {
+    // The e-pawns block each other, the black pawn on f5 can take on e4
+    let mut game = Game::from_fen("4k3/8/8/4pp2/4P3/8/8/4K3 w - - 0 1").unwrap();
+    assert!(!game.play_move(square("e4"), square("e5")));
+    assert!(game.play_move(square("e4"), square("f5")));
+    assert!(!game.play_move(square("e5"), square("f4")));
+    assert!(game.play_move(square("e5"), square("e4")));
+}
@@ -0,0 +1,55 @@
+use cherris::*;
+
+fn evaluate(fen: &str) -> i32 {
+    let game = Game::from_fen(fen).unwrap();
+    game.board().evaluate(game.white_turn())
+}
+
+#[test]
+fn test_evaluation_is_symmetric() {
+    assert_eq!(evaluate(STARTING_FEN), 0);
+
+    // The same position with the colours swapped scores the same for the side to move
+    let white = evaluate("r1bqkbnr/pppp1ppp/2n5/4p3/4P3/5N2/PPPP1PPP/RNBQKB1R w KQkq - 2 3");
+    let black = evaluate("rnbqkb1r/pppp1ppp/5n2/4p3/4P3/2N5/PPPP1PPP/R1BQKBNR b KQkq - 2 3");
+    assert_eq!(white, black);
+
+    // And the other side sees the opposite score
+    let game = Game::from_fen("4k3/8/8/8/8/8/PPP5/4K3 w - - 0 1").unwrap();
+    assert_eq!(game.board().evaluate(true), -game.board().evaluate(false));
+    assert!(game.board().evaluate(true) > 0);
+}
+
+#[test]
+fn test_evaluation_counts_material() {
+    // Black is missing the queen
+    assert!(evaluate("rnb1kbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1") > 800);
+    // White is missing a knight
+    assert!(evaluate("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/R1BQKBNR b KQkq - 0 1") > 250);
+}
+
+#[test]
+fn test_evaluation_prefers_good_squares() {
+    // A knight in the centre is worth more than one on the rim
+    let centre = evaluate("4k3/8/8/8/4N3/8/8/4K3 w - - 0 1");
+    let rim = evaluate("4k3/8/8/8/7N/8/8/4K3 w - - 0 1");
+    assert!(centre > rim);
+
+    // Advanced pawns are worth more
+    let advanced = evaluate("4k3/8/1P6/8/8/8/8/4K3 w - - 0 1");
+    let home = evaluate("4k3/8/8/8/8/8/1P6/4K3 w - - 0 1");
+    assert!(advanced > home);
+}
+
+#[test]
+fn test_evaluation_depends_on_the_phase() {
+    // With the queens on, the king is safer behind its pawns
+    let castled = evaluate("rq2k2r/pppppppp/8/8/8/8/PPPPPPPP/RQ3RK1 w - - 0 1");
+    let centre = evaluate("rq2k2r/pppppppp/8/8/8/4K3/PPPPPPPP/RQ3R2 w - - 0 1");
+    assert!(castled > centre);
+
+    // In the endgame it belongs in the centre
+    let corner = evaluate("4k3/p7/8/8/8/8/P7/6K1 w - - 0 1");
+    let centre = evaluate("4k3/p7/8/8/8/4K3/P7/8 w - - 0 1");
+    assert!(centre > corner);
+}
@@ -0,0 +1,145 @@
+use cherris::*;
+
+#[test]
+fn test_starting_fen_matches_init() {
+    let board = Board::from_fen(STARTING_FEN).unwrap();
+    assert_eq!(board.state, Board::init().state);
+
+    let game = Game::from_fen(STARTING_FEN).unwrap();
+    assert!(game.white_turn());
+    assert_eq!(
+        game.castling_rights(),
+        CASTLE_WHITE_KINGSIDE
+            | CASTLE_WHITE_QUEENSIDE
+            | CASTLE_BLACK_KINGSIDE
+            | CASTLE_BLACK_QUEENSIDE
+    );
+    assert_eq!(game.en_passant(), None);
+    assert_eq!(game.halfmove_clock(), 0);
+    assert_eq!(game.fullmove_number(), 1);
+}
+
+#[test]
+fn test_fen_game_fields() {
+    let game =
+        Game::from_fen("rnbqkbnr/ppp1pppp/8/8/3pP3/8/PPPP1PPP/RNBQKBNR b Kq e3 0 3").unwrap();
+    assert!(!game.white_turn());
+    assert_eq!(
+        game.castling_rights(),
+        CASTLE_WHITE_KINGSIDE | CASTLE_BLACK_QUEENSIDE
+    );
+    assert_eq!(
+        game.en_passant(),
+        Some(position_helper::letter_to_position_byte(String::from("e3")))
+    );
+    assert_eq!(game.fullmove_number(), 3);
+
+    let e4 = position_helper::letter_to_position_byte(String::from("e4"));
+    let white_pawn = *game.board().pieces.get(&e4).unwrap();
+    assert_eq!(white_pawn & 0b11000000, 0b11000000); // exists and is white
+}
+
+#[test]
+fn test_fen_without_clocks() {
+    let game = Game::from_fen("8/8/4k3/8/8/8/8/4K2R w K -").unwrap();
+    assert_eq!(game.halfmove_clock(), 0);
+    assert_eq!(game.fullmove_number(), 1);
+    assert_eq!(game.board().pieces.len(), 3);
+}
+
+#[test]
+fn test_fen_errors_name_the_field() {
+    assert_eq!(
+        Board::from_fen("8/8/8 w - - 0 1").unwrap_err(),
+        FenError::PiecePlacement(String::from("expected 8 ranks, found 3"))
+    );
+    assert!(matches!(
+        Game::from_fen("4k3/8/8/8/8/8/8/4K3 x - - 0 1"),
+        Err(FenError::SideToMove(_))
+    ));
+    assert!(matches!(
+        Game::from_fen("4k3/8/8/8/8/8/8/4K3 w KX - 0 1"),
+        Err(FenError::CastlingRights(_))
+    ));
+    assert!(matches!(
+        Game::from_fen("4k3/8/8/8/8/8/8/4K3 w - e4 0 1"),
+        Err(FenError::EnPassant(_))
+    ));
+    assert!(matches!(
+        Game::from_fen("4k3/8/8/8/8/8/8/4K3 w - - x 1"),
+        Err(FenError::HalfmoveClock(_))
+    ));
+    assert!(matches!(
+        Game::from_fen("4k3/8/8/8/8/8/8/4K3 w - - 0 0"),
+        Err(FenError::FullmoveNumber(_))
+    ));
+    assert!(matches!(
+        Game::from_fen("4k3/8/8/8/8/8/8/4K3 w -"),
+        Err(FenError::FieldCount(3))
+    ));
+    assert!(matches!(
+        Game::from_fen("4k3/8/8/8/8/8/8/8 w - - 0 1"),
+        Err(FenError::PiecePlacement(_))
+    ));
+}
+
+#[test]
+fn test_fen_rejects_opponent_in_check() {
+    // White to move could take the black king on e8
+    assert_eq!(
+        Game::from_fen("4k2R/8/8/8/8/8/8/4K3 w - - 0 1").err(),
+        Some(FenError::OpponentInCheck)
+    );
+    // The side to move may be in check
+    assert!(Game::from_fen("4k2R/8/8/8/8/8/8/4K3 b - - 0 1").is_ok());
+}
+
+#[test]
+fn test_fen_drops_castling_rights_without_pieces() {
+    let game = Game::from_fen("4k3/8/8/8/8/8/8/4K3 w KQkq - 0 1").unwrap();
+    assert_eq!(game.to_fen(), "4k3/8/8/8/8/8/8/4K3 w - - 0 1");
+    assert_eq!(
+        game.zobrist_key(),
+        Game::from_fen("4k3/8/8/8/8/8/8/4K3 w - - 0 1")
+            .unwrap()
+            .zobrist_key()
+    );
+
+    // Only the rights whose king and rook are at home are kept
+    let game = Game::from_fen("r3k3/8/8/8/8/8/8/4K2R w KQkq - 0 1").unwrap();
+    assert_eq!(game.to_fen(), "r3k3/8/8/8/8/8/8/4K2R w Kq - 0 1");
+}
+
+#[test]
+fn test_fen_round_trip() {
+    let fens = [
+        STARTING_FEN,
+        "rnbqkbnr/ppp1pppp/8/8/3pP3/8/PPPP1PPP/RNBQKBNR b Kq e3 0 3",
+        "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1",
+        "8/8/4k3/8/8/8/8/4K2R w - - 12 40",
+    ];
+    for fen in fens {
+        assert_eq!(Game::from_fen(fen).unwrap().to_fen(), fen);
+    }
+
+    let board = Board::init();
+    assert_eq!(
+        board.to_fen(),
+        "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR"
+    );
+    assert_eq!(Board::from_fen(&board.to_fen()).unwrap().state, board.state);
+}
+
+#[test]
+fn test_fen_after_moves() {
+    let mut game = Game::init();
+    let square = |s: &str| position_helper::letter_to_position_byte(String::from(s));
+
+    assert!(game.play_move(square("e2"), square("e4")));
+    assert!(game.play_move(square("e7"), square("e5")));
+    assert!(game.play_move(square("g1"), square("f3")));
+    assert_eq!(
+        game.to_fen(),
+        "rnbqkbnr/pppp1ppp/8/4p3/4P3/5N2/PPPP1PPP/RNBQKB1R b KQkq - 1 2"
+    );
+}
@@ -0,0 +1,41 @@
+mod common;
+
+use cherris::*;
+use common::square;
+
+#[test]
+fn test_fools_mate() {
+    let mut game = Game::init();
+    assert!(game.play_move(square("f2"), square("f3")));
+    assert!(game.play_move(square("e7"), square("e5")));
+    assert!(game.play_move(square("g2"), square("g4")));
+    assert!(!game.game_done());
+    assert!(game.play_move(square("d8"), square("h4")));
+
+    assert!(game.game_done());
+    assert!(game.is_in_check());
+    assert_eq!(game.result(), Some(GameResult::BlackWins));
+    assert_eq!(game.termination(), Some(Termination::Checkmate));
+
+    // No more moves once the game is over
+    assert!(!game.play_move(square("a2"), square("a3")));
+}
+
+#[test]
+fn test_stalemate() {
+    let mut game = Game::from_fen("7k/8/6Q1/8/8/8/8/K7 w - - 0 1").unwrap();
+    assert!(game.play_move(square("g6"), square("f7")));
+    assert_eq!(game.result(), Some(GameResult::Draw));
+    assert_eq!(game.termination(), Some(Termination::Stalemate));
+}
+
+#[test]
+fn test_position_loaded_as_mate() {
+    let game = Game::from_fen("R5k1/5ppp/8/8/8/8/8/6K1 b - - 0 1").unwrap();
+    assert_eq!(game.result(), Some(GameResult::WhiteWins));
+    assert_eq!(game.termination(), Some(Termination::Checkmate));
+
+    let game = Game::from_fen(STARTING_FEN).unwrap();
+    assert_eq!(game.result(), None);
+    assert_eq!(game.termination(), None);
+}
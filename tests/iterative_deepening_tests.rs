@@ -0,0 +1,106 @@
+use std::{
+    io::Cursor,
+    time::{Duration, Instant},
+};
+
+use cherris::{
+    cherris_engine::{uci, Engine, SearchLimits},
+    Game, Move,
+};
+
+#[test]
+fn test_every_depth_is_reported() {
+    let mut engine = Engine::new();
+    let limits = SearchLimits {
+        depth: Some(4),
+        ..SearchLimits::default()
+    };
+    let mut infos = Vec::new();
+    let result = engine.search_with_info(&limits, |info| infos.push(info.clone()));
+
+    let depths: Vec<u32> = infos.iter().map(|info| info.depth).collect();
+    assert_eq!(depths, vec![1, 2, 3, 4]);
+    assert_eq!(result.depth, 4);
+    let last = infos.last().unwrap();
+    assert_eq!(last.score, result.score);
+    assert_eq!(last.principal_variation.first().copied(), result.best_move);
+    assert_eq!(last.principal_variation.len(), 4);
+    assert!(infos.windows(2).all(|pair| pair[0].nodes < pair[1].nodes));
+}
+
+#[test]
+fn test_search_keeps_to_its_time() {
+    let fen = "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1";
+    let mut engine = Engine::new();
+    engine.set_position(fen, &[]).unwrap();
+
+    let limits = SearchLimits {
+        movetime: Some(Duration::from_millis(200)),
+        ..SearchLimits::default()
+    };
+    let start = Instant::now();
+    let result = engine.search(&limits);
+    assert!(start.elapsed() < Duration::from_millis(400));
+    assert!(result.best_move.is_some());
+
+    // With little left on the clock the engine plays fast, even when the other side has plenty
+    let limits = SearchLimits {
+        wtime: Some(Duration::from_millis(500)),
+        btime: Some(Duration::from_secs(600)),
+        ..SearchLimits::default()
+    };
+    let start = Instant::now();
+    let result = engine.search(&limits);
+    assert!(start.elapsed() < Duration::from_millis(300));
+    assert!(result.best_move.is_some());
+}
+
+#[test]
+fn test_uci_info_lines() {
+    let mut output = Vec::new();
+    let commands = "position fen 6k1/5ppp/8/8/8/8/8/R5K1 w - - 0 1\ngo depth 3\n\
+                    position fen 6k1/5ppp/8/8/8/8/5PPP/r5K1 w - - 0 1\ngo depth 2\nquit\n";
+    uci::run(Cursor::new(commands), &mut output).unwrap();
+    let output = String::from_utf8(output).unwrap();
+    let lines: Vec<&str> = output.lines().collect();
+
+    assert_eq!(lines.len(), 5);
+    assert!(lines[0].starts_with("info depth 1 score cp "));
+    assert!(lines[1].starts_with("info depth 2 score mate 1 nodes "));
+    assert!(lines[2].starts_with("info depth 3 score mate 1 nodes "));
+    assert!(lines[2].ends_with(" pv a1a8"));
+    assert_eq!(lines[3], "bestmove a1a8");
+    // White to move is already mated
+    assert_eq!(lines[4], "bestmove 0000");
+}
+
+#[test]
+fn test_tiny_budget_still_searches() {
+    // Ra1xa8 wins the queen, the knight moves come first
+    let fen = "q3k3/8/8/8/8/8/8/R3K1N1 w - - 0 1";
+    let capture = Some(Move::from_uci(&Game::from_fen(fen).unwrap(), "a1a8").unwrap());
+    let budgets = [
+        SearchLimits {
+            movetime: Some(Duration::from_millis(1)),
+            ..SearchLimits::default()
+        },
+        SearchLimits {
+            movetime: Some(Duration::from_millis(30)),
+            ..SearchLimits::default()
+        },
+        SearchLimits {
+            wtime: Some(Duration::from_millis(20)),
+            btime: Some(Duration::from_millis(20)),
+            ..SearchLimits::default()
+        },
+    ];
+    for limits in budgets {
+        let mut engine = Engine::new();
+        engine.set_position(fen, &[]).unwrap();
+        assert_ne!(engine.game().legal_moves().first().copied(), capture);
+
+        let result = engine.search(&limits);
+        assert_eq!(result.best_move, capture);
+        assert!(result.depth >= 1);
+    }
+}
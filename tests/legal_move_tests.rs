@@ -0,0 +1,61 @@
+mod common;
+
+use cherris::*;
+use common::square;
+
+fn moves_from(game: &Game, letters: &str) -> Vec<String> {
+    let mut destinations: Vec<String> = game
+        .legal_moves()
+        .iter()
+        .filter(|chess_move| chess_move.initial_position() == square(letters))
+        .map(|chess_move| position_helper::position_byte_to_letter(chess_move.final_position()))
+        .collect();
+    destinations.sort();
+    destinations
+}
+
+#[test]
+fn test_starting_position_has_twenty_moves() {
+    let game = Game::init();
+    assert_eq!(game.legal_moves().len(), 20);
+    assert!(!game.is_in_check());
+}
+
+#[test]
+fn test_pinned_piece_cannot_leave_the_pin() {
+    // The knight on e2 is pinned by the rook, the bishop on d2 can only slide along its pin
+    let game = Game::from_fen("4r2k/8/8/b7/8/8/3BN3/4K3 w - - 0 1").unwrap();
+    assert!(moves_from(&game, "e2").is_empty());
+    assert_eq!(moves_from(&game, "d2"), vec!["a5", "b4", "c3"]);
+}
+
+#[test]
+fn test_double_check_only_allows_king_moves() {
+    let game = Game::from_fen("4k3/8/8/8/1b6/8/3N4/r3K3 w - - 0 1").unwrap();
+    assert!(game.is_in_check());
+    assert!(game
+        .legal_moves()
+        .iter()
+        .all(|chess_move| chess_move.initial_position() == square("e1")));
+    assert_eq!(moves_from(&game, "e1"), vec!["e2", "f2"]);
+}
+
+#[test]
+fn test_king_cannot_walk_into_attack() {
+    let mut game = Game::from_fen("4k3/8/8/8/8/8/r7/4K3 w - - 0 1").unwrap();
+    assert_eq!(moves_from(&game, "e1"), vec!["d1", "f1"]);
+    assert!(!game.play_move(square("e1"), square("e2")));
+    assert!(game.play_move(square("e1"), square("f1")));
+}
+
+#[test]
+fn test_piece_is_move_valid() {
+    let board = Board::from_fen("4r2k/8/8/8/8/8/4N3/4K3 w - - 0 1").unwrap();
+    let knight_binary = *board.pieces.get(&square("e2")).unwrap();
+    let knight = Piece::init_from_binary(knight_binary);
+    assert!(!knight.is_move_valid(square("c3"), board.clone()));
+
+    let board = Board::from_fen("7k/8/8/8/8/8/4N3/4K3 w - - 0 1").unwrap();
+    assert!(knight.is_move_valid(square("c3"), board.clone()));
+    assert!(!knight.is_move_valid(square("e1"), board));
+}
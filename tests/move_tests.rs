@@ -0,0 +1,58 @@
+mod common;
+
+use cherris::*;
+use common::square;
+
+#[test]
+fn test_move_packing() {
+    let chess_move = Move::new(square("e2"), square("e4"), Move::DOUBLE_PAWN_PUSH);
+    assert_eq!(chess_move.initial_position(), square("e2"));
+    assert_eq!(chess_move.final_position(), square("e4"));
+    assert!(chess_move.is_double_pawn_push());
+    assert!(!chess_move.is_capture());
+    assert_eq!(Move::from_u16(chess_move.to_u16()), chess_move);
+    assert_eq!(chess_move.to_string(), "e2e4");
+
+    let promotion =
+        Move::new_promotion(square("b7"), square("a8"), PieceType::Knight, true).unwrap();
+    assert!(promotion.is_promotion());
+    assert!(promotion.is_capture());
+    assert_eq!(promotion.promotion(), Some(PieceType::Knight));
+    assert_eq!(promotion.to_string(), "b7a8n");
+    assert!(Move::new_promotion(square("b7"), square("b8"), PieceType::King, false).is_none());
+
+    let en_passant = Move::new(square("e5"), square("d6"), Move::EN_PASSANT);
+    assert!(en_passant.is_en_passant());
+    assert!(en_passant.is_capture());
+}
+
+#[test]
+fn test_legal_moves_carry_flags() {
+    let game = Game::from_fen("r3k3/1P6/8/3pP3/8/8/8/R3K2R w KQq d6 0 1").unwrap();
+    let moves = game.legal_moves();
+    let find = |text: &str| moves.iter().find(|m| m.to_string() == text).copied();
+
+    assert_eq!(find("e1g1").unwrap().flags(), Move::KING_CASTLE);
+    assert_eq!(find("e1c1").unwrap().flags(), Move::QUEEN_CASTLE);
+    assert!(find("e5d6").unwrap().is_en_passant());
+    assert!(find("a1a8").unwrap().is_capture());
+    for promotion in ["b7b8q", "b7b8r", "b7b8b", "b7b8n", "b7a8q"] {
+        assert!(find(promotion).unwrap().is_promotion(), "{}", promotion);
+    }
+    assert!(find("b7a8n").unwrap().is_capture());
+}
+
+#[test]
+fn test_game_records_played_moves() {
+    let mut game = Game::init();
+    assert!(game.play_move(square("e2"), square("e4")));
+    let knight_move = Move::new(square("g8"), square("f6"), Move::QUIET);
+    assert!(game.make_move(knight_move));
+    // Moves that are not legal, or carry the wrong flags, are refused
+    assert!(!game.make_move(Move::new(square("d2"), square("d5"), Move::QUIET)));
+    assert!(!game.make_move(Move::new(square("d2"), square("d4"), Move::QUIET)));
+
+    let played: Vec<String> = game.moves_done().iter().map(|m| m.to_string()).collect();
+    assert_eq!(played, vec!["e2e4", "g8f6"]);
+    assert!(game.moves_done()[0].is_double_pawn_push());
+}
@@ -0,0 +1,59 @@
+use cherris::*;
+
+// Expected counts from https://www.chessprogramming.org/Perft_Results
+fn assert_perft(fen: &str, expected: &[u64]) {
+    let mut game = Game::from_fen(fen).unwrap();
+    for (depth, &nodes) in expected.iter().enumerate() {
+        assert_eq!(
+            game.perft(depth as u32 + 1),
+            nodes,
+            "depth {} of {}",
+            depth + 1,
+            fen
+        );
+    }
+    // walking the tree leaves the game untouched
+    assert_eq!(game.to_fen(), Game::from_fen(fen).unwrap().to_fen());
+}
+
+#[test]
+fn test_perft_starting_position() {
+    assert_perft(STARTING_FEN, &[20, 400, 8902]);
+}
+
+#[test]
+fn test_perft_kiwipete() {
+    assert_perft(
+        "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1",
+        &[48, 2039],
+    );
+}
+
+#[test]
+fn test_perft_endgame() {
+    assert_perft(
+        "8/2p5/3p4/KP5r/1R3p1k/8/4P1P1/8 w - - 0 1",
+        &[14, 191, 2812],
+    );
+}
+
+#[test]
+fn test_perft_promotions() {
+    assert_perft(
+        "r3k2r/Pppp1ppp/1b3nbN/nP6/BBP1P3/q4N2/Pp1P2PP/R2Q1RK1 w kq - 0 1",
+        &[6, 264, 9467],
+    );
+    assert_perft(
+        "rnbq1k1r/pp1Pbppp/2p5/8/2B5/8/PPP1NnPP/RNBQK2R w KQ - 1 8",
+        &[44, 1486],
+    );
+}
+
+#[test]
+fn test_perft_divide() {
+    let mut game = Game::init();
+    let divide = game.perft_divide(2);
+    assert_eq!(divide.len(), 20);
+    assert!(divide.iter().all(|&(_, nodes)| nodes == 20));
+    assert_eq!(divide.iter().map(|&(_, nodes)| nodes).sum::<u64>(), 400);
+}
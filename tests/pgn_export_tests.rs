@@ -0,0 +1,88 @@
+use std::time::Duration;
+
+use cherris::*;
+
+fn play_san(game: &mut Game, moves: &[&str]) {
+    for san in moves {
+        let chess_move = Move::from_san(game, san).unwrap();
+        assert!(game.make_move(chess_move));
+    }
+}
+
+#[test]
+fn test_export_finished_game() {
+    let mut game = Game::init();
+    play_san(
+        &mut game,
+        &["e4", "e5", "Bc4", "Nc6", "Qh5", "Nf6", "Qxf7#"],
+    );
+    assert_eq!(
+        game.to_pgn(),
+        r#"[Event "?"]
+[Site "?"]
+[Date "????.??.??"]
+[Round "?"]
+[White "?"]
+[Black "?"]
+[Result "1-0"]
+
+1. e4 e5 2. Bc4 Nc6 3. Qh5 Nf6 4. Qxf7# 1-0
+"#
+    );
+}
+
+#[test]
+fn test_export_tags_and_clocks() {
+    let mut game = Game::from_fen("4k3/8/8/8/8/8/8/R3K3 b Q - 0 30").unwrap();
+    play_san(&mut game, &["Kd7", "O-O-O+", "Ke6"]);
+    let tags = vec![
+        (String::from("Annotator"), String::from("cherris")),
+        (String::from("White"), String::from("Player \"one\"")),
+        (String::from("Event"), String::from("Club game")),
+    ];
+    let clocks = [
+        Duration::from_secs(298),
+        Duration::from_secs(3725),
+        Duration::from_secs(290),
+    ];
+    assert_eq!(
+        write_pgn(&game, &tags, Some(&clocks)),
+        r#"[Event "Club game"]
+[Site "?"]
+[Date "????.??.??"]
+[Round "?"]
+[White "Player \"one\""]
+[Black "?"]
+[Result "*"]
+[Annotator "cherris"]
+[SetUp "1"]
+[FEN "4k3/8/8/8/8/8/8/R3K3 b Q - 0 30"]
+
+30... Kd7 {[%clk 0:04:58]} 31. O-O-O+ {[%clk 1:02:05]} 31... Ke6
+{[%clk 0:04:50]} *
+"#
+    );
+}
+
+#[test]
+fn test_export_round_trip() {
+    let pgn = r#"[Event "F/S Return Match"]
+[Site "Belgrade, Serbia JUG"]
+[Date "1992.11.04"]
+[Round "29"]
+[White "Fischer, Robert J."]
+[Black "Spassky, Boris V."]
+[Result "1/2-1/2"]
+
+1. e4 e5 2. Nf3 Nc6 3. Bb5 a6 4. Ba4 Nf6 5. O-O Be7 6. Re1 b5 7. Bb3 d6 8. c3
+O-O 9. h3 Nb8 10. d4 Nbd7 11. c4 c6 12. cxb5 axb5 13. Nc3 Bb7 14. Bg5 b4 15.
+Nb1 h6 16. Bh4 c5 17. dxe5 Nxe4 18. Bxe7 Qxe7 19. exd6 Qf6 20. Nbd2 Nxd6 21.
+Nc4 Nxc4 22. Bxc4 Nb6 23. Ne5 Rae8 24. Bxf7+ Rxf7 25. Nxf7 Rxe1+ 26. Qxe1 Kxf7
+27. Qe3 Qg5 28. Qxg5 hxg5 29. b3 Ke6 30. a3 Kd6 31. axb4 cxb4 32. Ra5 Nd5 33.
+f3 Bc8 34. Kf2 Bf5 35. Ra7 g6 36. Ra6+ Kc5 37. Ke1 Nf4 38. g3 Nxh3 39. Kd2 Kb5
+40. Rd6 Kc5 41. Ra6 Nf2 42. g4 Bd3 43. Re6 1/2-1/2
+"#;
+    let games = read_pgn(pgn).unwrap();
+    assert_eq!(games[0].to_pgn(), pgn);
+    assert!(pgn.lines().all(|line| line.len() < 80));
+}
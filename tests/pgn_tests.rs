@@ -0,0 +1,162 @@
+use cherris::*;
+
+const GAMES: &str = r#"[Event "F/S Return Match"]
+[Site "Belgrade, Serbia JUG"]
+[Date "1992.11.04"]
+[Round "29"]
+[White "Fischer, Robert J."]
+[Black "Spassky, Boris V."]
+[Result "1/2-1/2"]
+
+1. e4 e5 2. Nf3 Nc6 3. Bb5 a6 {This opening is called the Ruy Lopez.}
+4. Ba4 Nf6 5. O-O Be7 6. Re1 b5 7. Bb3 d6 8. c3 O-O 9. h3 Nb8 10. d4 Nbd7
+11. c4 c6 12. cxb5 axb5 13. Nc3 Bb7 14. Bg5 b4 15. Nb1 h6 16. Bh4 c5 17. dxe5
+Nxe4 18. Bxe7 Qxe7 19. exd6 Qf6 20. Nbd2 Nxd6 21. Nc4 Nxc4 22. Bxc4 Nb6
+23. Ne5 Rae8 24. Bxf7+ Rxf7 25. Nxf7 Rxe1+ 26. Qxe1 Kxf7 27. Qe3 Qg5 28. Qxg5
+hxg5 29. b3 Ke6 30. a3 Kd6 31. axb4 cxb4 32. Ra5 Nd5 33. f3 Bc8 34. Kf2 Bf5
+35. Ra7 g6 36. Ra6+ Kc5 37. Ke1 Nf4 38. g3 Nxh3 39. Kd2 Kb5 40. Rd6 Kc5 41. Ra6
+Nf2 42. g4 Bd3 43. Re6 1/2-1/2
+
+[Event "Casual"]
+[White "A"]
+[Black "B"]
+[Result "1-0"]
+
+% an escaped line
+1.e4 e5 2.Bc4 $1 (2. Nf3 Nc6 (2... d6) 3. Bb5) Nc6 ; a rest of line comment
+3.Qh5 Nf6?? 4.Qxf7# 1-0
+"#;
+
+#[test]
+fn test_read_games() {
+    let games = read_pgn(GAMES).unwrap();
+    assert_eq!(games.len(), 2);
+
+    let first = &games[0];
+    assert_eq!(first.tag("White"), Some("Fischer, Robert J."));
+    assert_eq!(first.tag("Opening"), None);
+    assert_eq!(first.tags.len(), 7);
+    assert_eq!(first.result, "1/2-1/2");
+    assert_eq!(first.game.moves_done().len(), 85);
+    assert_eq!(
+        first.game.to_fen(),
+        "8/8/4R1p1/2k3p1/1p4P1/1P1b1P2/3K1n2/8 b - - 2 43"
+    );
+
+    let second = &games[1];
+    assert_eq!(second.result, "1-0");
+    assert_eq!(second.game.termination(), Some(Termination::Checkmate));
+    assert_eq!(second.game.result(), Some(GameResult::WhiteWins));
+}
+
+#[test]
+fn test_read_from_fen_tag() {
+    let pgn = r#"[SetUp "1"]
+[FEN "4k3/8/8/8/8/8/8/4K2R w K - 0 30"]
+
+30. O-O Kd7 31. Rd1+ *"#;
+    let games = read_pgn(pgn).unwrap();
+    assert_eq!(games[0].result, "*");
+    assert_eq!(games[0].game.to_fen(), "8/3k4/8/8/8/8/8/3R2K1 b - - 3 31");
+}
+
+#[test]
+fn test_read_games_played_past_an_automatic_draw() {
+    // The 75-move rule ends the game after Ra2, this game went on as allowed before 2014
+    let pgn = r#"[SetUp "1"]
+[FEN "4k3/8/8/8/8/8/8/R3K3 w - - 149 100"]
+
+100. Ra2 Kd7 101. Ra7+ 1/2-1/2"#;
+    let games = read_pgn(pgn).unwrap();
+    let game = &games[0].game;
+    assert_eq!(game.to_fen(), "8/R2k4/8/8/8/8/8/4K3 b - - 152 101");
+    assert_eq!(game.termination(), Some(Termination::SeventyFiveMoveRule));
+
+    // Moves after a checkmate are still refused
+    let (_, error) = read_error("1. f3 e5 2. g4 Qh4# 3. a3 *");
+    assert!(matches!(
+        error,
+        PgnError::IllegalMove { move_number: 3, .. }
+    ));
+}
+
+// The error read_pgn gives, with the line it was found on
+fn read_error(pgn: &str) -> (usize, PgnError) {
+    match read_pgn(pgn) {
+        Err(PgnError::Line { line, error }) => (line, *error),
+        other => panic!(
+            "expected an error with its line, got {:?}",
+            other.map(|_| ())
+        ),
+    }
+}
+
+#[test]
+fn test_illegal_move_names_the_move() {
+    let (line, error) = read_error("1. e4 e5 2. Nf3 Nf3 3. d4 *");
+    assert_eq!(line, 1);
+    assert!(matches!(
+        &error,
+        PgnError::IllegalMove { move_number: 2, white_turn: false, san, .. } if san == "Nf3"
+    ));
+    assert!(error.to_string().starts_with("move 2... Nf3"));
+
+    let error = read_pgn("1. e4 e5 2. Ke3 *").err().unwrap();
+    assert_eq!(
+        error.to_string(),
+        "line 1: move 2. Ke3: Ke3 is not a legal move"
+    );
+}
+
+#[test]
+fn test_malformed_pgn() {
+    assert!(matches!(
+        read_error("1. e4 {never closed"),
+        (1, PgnError::Unterminated(_))
+    ));
+    assert!(matches!(
+        read_error("1. e4 (1. d4 d5"),
+        (1, PgnError::Unterminated(_))
+    ));
+    assert!(matches!(
+        read_error("[Event]\n1. e4 *"),
+        (1, PgnError::Tag(_))
+    ));
+    assert!(matches!(
+        read_error("[Event \"Unclosed\"\n1. e4 *"),
+        (1, PgnError::Unterminated(_))
+    ));
+    assert!(matches!(
+        read_error("[FEN \"8/8/8/8/8/8/8/8 w - - 0 1\"]\n*"),
+        (1, PgnError::Fen(_))
+    ));
+    assert!(matches!(
+        read_error("1. f3 e5 2. g4 Qh4# 3. a3 0-1"),
+        (1, PgnError::IllegalMove { move_number: 3, .. })
+    ));
+}
+
+#[test]
+fn test_brackets_and_quotes_in_tag_values() {
+    let games =
+        read_pgn("[Event \"Test [x]\"]\n[Annotator \"\\\"Bracket]\\\" \\\\\"]\n1. e4 *").unwrap();
+    assert_eq!(games[0].tag("Event"), Some("Test [x]"));
+    assert_eq!(games[0].tag("Annotator"), Some("\"Bracket]\" \\"));
+    assert_eq!(games[0].game.moves_done().len(), 1);
+}
+
+#[test]
+fn test_errors_give_the_line() {
+    // A third game breaks on the last of its lines, after a blank line and a comment running over
+    // two lines
+    let pgn = format!(
+        "{}\n[Event \"Broken\"]\n{{two\nlines}}\n1. e4 e5 2. Ke3 *\n",
+        GAMES
+    );
+    let (line, error) = read_error(&pgn);
+    assert_eq!(line, GAMES.lines().count() + 5);
+    assert!(matches!(
+        error,
+        PgnError::IllegalMove { move_number: 2, .. }
+    ));
+}
@@ -40,7 +40,7 @@ fn test_state_pieces_parity() {
 
 #[test]
 fn test_pawn_initial_move_emtpy_board() {
-    let board = Board::init();
+    let board = Board::empty();
     let pos_string: String = String::from("a2");
     let position = position_helper::letter_to_position_byte(pos_string);
     let white_pawn = Piece::init_from_binary(PIECE_BIT + WHITE_BIT + PAWN_BIT);
@@ -54,7 +54,7 @@ fn test_pawn_initial_move_emtpy_board() {
 
 #[test]
 fn test_king_moves_empty_board() {
-    let board = Board::init();
+    let board = Board::empty();
     let pos_string: String = String::from("a1");
     let position = position_helper::letter_to_position_byte(pos_string);
     let king = Piece::init_from_binary(PIECE_BIT + WHITE_BIT + KING);
@@ -73,7 +73,7 @@ fn test_king_moves_empty_board() {
 
 #[test]
 fn test_rook_moves_empty_board() {
-    let board = Board::init();
+    let board = Board::empty();
     let pos_string: String = String::from("d4");
     let position = position_helper::letter_to_position_byte(pos_string.clone());
     let rook = Piece::init_from_binary(PIECE_BIT + WHITE_BIT + ROOK);
@@ -86,14 +86,12 @@ fn test_rook_moves_empty_board() {
         "The positions from {} for the rook are: {:?}",
         pos_string, possible_positions
     );
-    let correct_position: HashSet<String> = HashSet::from(
-        [
-            "a4", "b4", "c4", "d1", "d2", "d3", "d5", "d6", "d7", "d8", "e4", "f4", "g4", "h4",
-        ]
-        .iter()
-        .map(|&x| String::from(x))
-        .collect::<HashSet<String>>(),
-    );
+    let correct_position: HashSet<String> = [
+        "a4", "b4", "c4", "d1", "d2", "d3", "d5", "d6", "d7", "d8", "e4", "f4", "g4", "h4",
+    ]
+    .iter()
+    .map(|&x| String::from(x))
+    .collect::<HashSet<String>>();
     assert_eq!(possible_positions, correct_position);
 }
 
@@ -118,7 +116,7 @@ fn test_rook_moves_starting_board() {
 }
 #[test]
 fn test_bishop_moves_empty_board() {
-    let board = Board::init();
+    let board = Board::empty();
     let pos_string: String = String::from("d4");
     let position = position_helper::letter_to_position_byte(pos_string.clone());
     let bishop = Piece::init_from_binary(PIECE_BIT + WHITE_BIT + BISHOP);
@@ -131,14 +129,12 @@ fn test_bishop_moves_empty_board() {
         "The positions from {} for the bishop are: {:?}",
         pos_string, possible_positions
     );
-    let correct_position: HashSet<String> = HashSet::from(
-        [
-            "a1", "a7", "b2", "b6", "c3", "c5", "e3", "e5", "f2", "f6", "g1", "g7", "h8",
-        ]
-        .iter()
-        .map(|&x| String::from(x))
-        .collect::<HashSet<String>>(),
-    );
+    let correct_position: HashSet<String> = [
+        "a1", "a7", "b2", "b6", "c3", "c5", "e3", "e5", "f2", "f6", "g1", "g7", "h8",
+    ]
+    .iter()
+    .map(|&x| String::from(x))
+    .collect::<HashSet<String>>();
     assert_eq!(possible_positions, correct_position);
 }
 
@@ -184,7 +180,7 @@ fn test_queen_moves_starting_board() {
 
 #[test]
 fn test_queen_moves_empty_board() {
-    let board = Board::init();
+    let board = Board::empty();
     let pos_string: String = String::from("d4");
     let position = position_helper::letter_to_position_byte(pos_string.clone());
     let queen = Piece::init_from_binary(PIECE_BIT + WHITE_BIT + QUEEN);
@@ -197,21 +193,19 @@ fn test_queen_moves_empty_board() {
         "The positions from {} for the queen are: {:?}",
         pos_string, possible_positions
     );
-    let correct_position: HashSet<String> = HashSet::from(
-        [
-            "a1", "a4", "a7", "b2", "b4", "b6", "c3", "c4", "c5", "d1", "d2", "d3", "d5", "d6",
-            "d7", "d8", "e3", "e4", "e5", "f2", "f4", "f6", "g1", "g4", "g7", "h4", "h8",
-        ]
-        .iter()
-        .map(|&x| String::from(x))
-        .collect::<HashSet<String>>(),
-    );
+    let correct_position: HashSet<String> = [
+        "a1", "a4", "a7", "b2", "b4", "b6", "c3", "c4", "c5", "d1", "d2", "d3", "d5", "d6", "d7",
+        "d8", "e3", "e4", "e5", "f2", "f4", "f6", "g1", "g4", "g7", "h4", "h8",
+    ]
+    .iter()
+    .map(|&x| String::from(x))
+    .collect::<HashSet<String>>();
     assert_eq!(possible_positions, correct_position);
 }
 
 #[test]
 fn test_knight_moves_empty_board() {
-    let board = Board::init();
+    let board = Board::empty();
     let pos_string: String = String::from("d4");
     let position = position_helper::letter_to_position_byte(pos_string.clone());
     let knight = Piece::init_from_binary(PIECE_BIT + WHITE_BIT + KNIGHT);
@@ -224,12 +218,10 @@ fn test_knight_moves_empty_board() {
         "The positions from {} for the knight are: {:?}",
         pos_string, possible_positions
     );
-    let correct_position: HashSet<String> = HashSet::from(
-        ["b3", "b5", "c2", "c6", "e2", "e6", "f3", "f5"]
-            .iter()
-            .map(|&x| String::from(x))
-            .collect::<HashSet<String>>(),
-    );
+    let correct_position: HashSet<String> = ["b3", "b5", "c2", "c6", "e2", "e6", "f3", "f5"]
+        .iter()
+        .map(|&x| String::from(x))
+        .collect::<HashSet<String>>();
     assert_eq!(possible_positions, correct_position);
 }
 
@@ -240,5 +232,41 @@ fn test_validate_position_in_board() {
     let final_string: String = String::from("a1");
     let final_position = position_helper::letter_to_position_byte(final_string.clone());
     let valid_position = position_helper::is_position_valid(final_position, &board, true);
-    assert_eq!(valid_position, false);
+    assert!(!valid_position);
+}
+
+#[test]
+fn test_moves_from_the_edges() {
+    let board = Board::empty();
+    let moves_from = |binary: u8, square: &str| {
+        let position = position_helper::letter_to_position_byte(String::from(square));
+        let mut positions: Vec<String> = Piece::init_from_binary(binary)
+            .possible_moves(position, &board)
+            .iter()
+            .map(|x| position_helper::position_byte_to_letter(*x))
+            .collect();
+        positions.sort();
+        positions
+    };
+
+    // Stepping left of the a-file or above rank 8 must not wrap around to another square
+    assert_eq!(moves_from(PIECE_BIT + KING, "a8"), vec!["a7", "b7", "b8"]);
+    assert_eq!(moves_from(PIECE_BIT + KNIGHT, "a8"), vec!["b6", "c7"]);
+    assert_eq!(moves_from(PIECE_BIT + KNIGHT, "h1"), vec!["f2", "g3"]);
+    assert_eq!(moves_from(PIECE_BIT + BISHOP, "h8").len(), 7);
+}
+
+#[test]
+fn test_off_board_positions() {
+    let a8 = position_helper::letter_to_position_byte(String::from("a8"));
+    let h1 = position_helper::letter_to_position_byte(String::from("h1"));
+    assert!(position_helper::is_on_board(a8));
+    assert!(position_helper::is_on_board(h1));
+    assert_eq!(position_helper::offset(a8, -1, 0), None);
+    assert_eq!(position_helper::offset(a8, 0, -1), None);
+    assert_eq!(position_helper::offset(h1, 1, 0), None);
+    assert_eq!(position_helper::offset(h1, 0, 1), None);
+    assert_eq!(position_helper::offset(h1, -7, -7), Some(a8));
+    assert!(!position_helper::validate_position(0x08));
+    assert!(!position_helper::validate_position(0x80));
 }
@@ -0,0 +1,68 @@
+mod common;
+
+use cherris::*;
+use common::square;
+
+#[test]
+fn test_promotion_defaults_to_queen() {
+    let mut game = Game::from_fen("4k3/P7/8/8/8/8/8/4K3 w - - 0 1").unwrap();
+    assert!(game.play_move(square("a7"), square("a8")));
+    assert_eq!(game.to_fen(), "Q3k3/8/8/8/8/8/8/4K3 b - - 0 1");
+}
+
+#[test]
+fn test_promotion_piece_choice() {
+    for (promotion, fen) in [
+        (PieceType::Rook, "R3k3/8/8/8/8/8/8/4K3 b - - 0 1"),
+        (PieceType::Bishop, "B3k3/8/8/8/8/8/8/4K3 b - - 0 1"),
+        (PieceType::Knight, "N3k3/8/8/8/8/8/8/4K3 b - - 0 1"),
+    ] {
+        let mut game = Game::from_fen("4k3/P7/8/8/8/8/8/4K3 w - - 0 1").unwrap();
+        assert!(game.play_move_with_promotion(square("a7"), square("a8"), promotion));
+        assert_eq!(game.to_fen(), fen);
+    }
+
+    let mut game = Game::from_fen("4k3/P7/8/8/8/8/8/4K3 w - - 0 1").unwrap();
+    assert!(!game.play_move_with_promotion(square("a7"), square("a8"), PieceType::King));
+}
+
+#[test]
+fn test_black_promotion_with_capture() {
+    let mut game = Game::from_fen("4k3/8/8/8/8/8/p7/1R2K3 b - - 0 1").unwrap();
+    assert!(game.play_move_with_promotion(square("a2"), square("b1"), PieceType::Knight));
+    assert_eq!(game.to_fen(), "4k3/8/8/8/8/8/8/1n2K3 w - - 0 2");
+}
+
+#[test]
+fn test_promoted_pieces_keep_unique_keys() {
+    let mut game = Game::from_fen("4k3/PPP5/8/8/8/8/8/Q3K3 w - - 0 1").unwrap();
+    assert!(game.play_move(square("a7"), square("a8")));
+    assert!(game.play_move(square("e8"), square("e7")));
+    assert!(game.play_move(square("b7"), square("b8")));
+
+    let pieces = &game.board().pieces;
+    let mut keys: Vec<u8> = pieces.values().copied().collect();
+    keys.sort();
+    keys.dedup();
+    assert_eq!(keys.len(), pieces.len());
+
+    // The promoted queens move like queens
+    let queen = Piece::init_from_binary(*pieces.get(&square("b8")).unwrap());
+    assert!(queen
+        .possible_moves(square("b8"), game.board())
+        .contains(&square("b2")));
+}
+
+#[test]
+fn test_fen_with_extra_pieces() {
+    // Three white queens and three black knights need promoted keys
+    let fen = "nnn1k3/8/8/8/8/8/PPPPPP2/QQQ1K3 w - - 0 1";
+    let game = Game::from_fen(fen).unwrap();
+    assert_eq!(game.to_fen(), fen);
+
+    let too_many = "4k3/8/8/8/8/8/PPPPPPPP/QQ2K3 w - - 0 1";
+    assert!(matches!(
+        Game::from_fen(too_many),
+        Err(FenError::PiecePlacement(_))
+    ));
+}
@@ -0,0 +1,96 @@
+use cherris::*;
+
+fn san_moves(game: &Game) -> Vec<String> {
+    let mut moves: Vec<String> = game
+        .legal_moves()
+        .into_iter()
+        .map(|chess_move| chess_move.to_san(game))
+        .collect();
+    moves.sort();
+    moves
+}
+
+#[test]
+fn test_play_game_in_san() {
+    let mut game = Game::init();
+    let moves = [
+        "e4", "e5", "Nf3", "Nc6", "Bb5", "a6", "Bxc6", "dxc6", "O-O", "f6", "d4", "exd4", "Nxd4",
+        "c5", "Nb3", "Qxd1", "Rxd1",
+    ];
+    for san in moves {
+        let chess_move = Move::from_san(&game, san).unwrap();
+        assert_eq!(chess_move.to_san(&game), san);
+        assert!(game.make_move(chess_move));
+    }
+    assert_eq!(
+        game.to_fen(),
+        "r1b1kbnr/1pp3pp/p4p2/2p5/4P3/1N6/PPP2PPP/RNBR2K1 b kq - 0 9"
+    );
+}
+
+#[test]
+fn test_san_disambiguation() {
+    // two knights on the same rank, two rooks on the same file
+    let game = Game::from_fen("3k4/8/8/8/RN3N2/8/R7/4K3 w - - 0 1").unwrap();
+    let moves = san_moves(&game);
+    for san in [
+        "Nbd5", "Nfd5", "Nbd3", "Nfd3", "R4a3", "R2a3", "Nc6+", "Ra1",
+    ] {
+        assert!(moves.contains(&String::from(san)), "{}", san);
+    }
+
+    assert_eq!(
+        Move::from_san(&game, "Nd5"),
+        Err(SanError::AmbiguousMove(String::from("Nd5")))
+    );
+    assert_eq!(Move::from_san(&game, "Nbd5").unwrap().to_string(), "b4d5");
+    assert_eq!(Move::from_san(&game, "R2a3").unwrap().to_string(), "a2a3");
+
+    // a third queen needs both the file and the rank
+    let game = Game::from_fen("3k4/8/8/8/8/8/5Q1Q/4K2Q w - - 0 1").unwrap();
+    let moves = san_moves(&game);
+    for san in ["Qh2g1", "Q1g1", "Qfg1", "Qfg3", "Qhg3"] {
+        assert!(moves.contains(&String::from(san)), "{}", san);
+    }
+    assert_eq!(Move::from_san(&game, "Qh2g1").unwrap().to_string(), "h2g1");
+}
+
+#[test]
+fn test_san_check_mate_and_promotion() {
+    let game = Game::from_fen("1r2k3/2P5/8/8/8/8/8/4K2R w K - 0 1").unwrap();
+    let moves = san_moves(&game);
+    assert!(moves.contains(&String::from("c8=Q+")));
+    assert!(moves.contains(&String::from("cxb8=N")));
+    assert!(moves.contains(&String::from("O-O")));
+    assert!(moves.contains(&String::from("Rh8+")));
+
+    let promotion = Move::from_san(&game, "cxb8=R").unwrap();
+    assert_eq!(promotion.promotion(), Some(PieceType::Rook));
+    assert_eq!(Move::from_san(&game, "c8Q").unwrap().to_san(&game), "c8=Q+");
+
+    let mate = Game::from_fen("6k1/5ppp/8/8/8/8/8/R3K3 w - - 0 1").unwrap();
+    let back_rank = Move::from_san(&mate, "Ra8").unwrap();
+    assert_eq!(back_rank.to_san(&mate), "Ra8#");
+}
+
+#[test]
+fn test_san_errors() {
+    let game = Game::init();
+    assert!(matches!(
+        Move::from_san(&game, "e5"),
+        Err(SanError::IllegalMove(_))
+    ));
+    assert!(matches!(
+        Move::from_san(&game, "O-O"),
+        Err(SanError::IllegalMove(_))
+    ));
+    for san in ["", "Zf3", "e9", "Nf", "hello", "e8=K"] {
+        assert!(
+            matches!(Move::from_san(&game, san), Err(SanError::Syntax(_))),
+            "{}",
+            san
+        );
+    }
+    // annotations are accepted
+    assert_eq!(Move::from_san(&game, "Nf3!?").unwrap().to_string(), "g1f3");
+}
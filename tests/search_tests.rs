@@ -0,0 +1,74 @@
+use cherris::cherris_engine::{Engine, SearchLimits, MATE_SCORE};
+
+fn search(fen: &str, depth: u32) -> (String, i32) {
+    let mut engine = Engine::new();
+    engine.set_position(fen, &[]).unwrap();
+    let limits = SearchLimits {
+        depth: Some(depth),
+        ..SearchLimits::default()
+    };
+    let result = engine.search(&limits);
+    let best_move = result.best_move.map_or(String::new(), |m| m.to_uci());
+    (best_move, result.score)
+}
+
+#[test]
+fn test_search_finds_mate() {
+    // Back rank mate in one
+    let (best_move, score) = search("6k1/5ppp/8/8/8/8/8/R5K1 w - - 0 1", 2);
+    assert_eq!(best_move, "a1a8");
+    assert_eq!(score, MATE_SCORE - 1);
+
+    // Mate in two, the rook is given up to open the b file
+    let (best_move, score) = search("kbK5/pp6/1P6/8/8/8/8/R7 w - - 0 1", 4);
+    assert_eq!(best_move, "a1a6");
+    assert_eq!(score, MATE_SCORE - 3);
+}
+
+#[test]
+fn test_search_wins_material() {
+    // The knight forks the king and the queen
+    let (best_move, score) = search("q3k3/7p/8/1N6/8/8/7P/4K3 w - - 0 1", 3);
+    assert_eq!(best_move, "b5c7");
+    assert!(score > 0);
+
+    // The hanging rook is taken, the defended one is not
+    let (best_move, _) = search("4k3/8/8/3r4/8/1p6/2r5/1Q2K3 w - - 0 1", 2);
+    assert_eq!(best_move, "b1b3");
+}
+
+#[test]
+fn test_search_without_moves() {
+    // Stalemate
+    let (best_move, score) = search("7k/5Q2/6K1/8/8/8/8/8 b - - 0 1", 3);
+    assert_eq!((best_move.as_str(), score), ("", 0));
+
+    // Checkmate
+    let (best_move, score) = search("R6k/6pp/8/8/8/8/8/6K1 b - - 0 1", 3);
+    assert_eq!((best_move.as_str(), score), ("", -MATE_SCORE));
+}
+
+#[test]
+fn test_search_avoids_stalemate() {
+    // Taking the knight would leave black without a move
+    let (best_move, score) = search("7k/1p3K1p/1P5P/8/8/8/1R6/1n6 w - - 0 1", 2);
+    assert_ne!(best_move, "b2b1");
+    assert!(score > 0);
+}
+
+#[test]
+fn test_search_stops_when_asked() {
+    let mut engine = Engine::new();
+    engine
+        .stop_flag()
+        .store(true, std::sync::atomic::Ordering::Relaxed);
+    let limits = SearchLimits {
+        depth: Some(20),
+        ..SearchLimits::default()
+    };
+    let result = engine.search(&limits);
+    assert!(engine
+        .game()
+        .legal_moves()
+        .contains(&result.best_move.unwrap()));
+}
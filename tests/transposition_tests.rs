@@ -0,0 +1,70 @@
+use std::io::Cursor;
+
+use cherris::cherris_engine::{uci, Engine, SearchLimits, MATE_SCORE};
+
+fn limits(depth: u32) -> SearchLimits {
+    SearchLimits {
+        depth: Some(depth),
+        ..SearchLimits::default()
+    }
+}
+
+#[test]
+fn test_table_saves_work() {
+    let fen = "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1";
+    let mut engine = Engine::new();
+    engine.set_position(fen, &[]).unwrap();
+    let first = engine.search(&limits(3));
+    let second = engine.search(&limits(3));
+    assert!(second.nodes < first.nodes);
+    assert_eq!(first.score, second.score);
+    assert_eq!(first.best_move, second.best_move);
+
+    // A new game starts from an empty table
+    engine.new_game();
+    engine.set_position(fen, &[]).unwrap();
+    assert_eq!(engine.search(&limits(3)).nodes, first.nodes);
+}
+
+#[test]
+fn test_table_keeps_mate_distances() {
+    let mut engine = Engine::new();
+    engine
+        .set_position("kbK5/pp6/1P6/8/8/8/8/R7 w - - 0 1", &[])
+        .unwrap();
+    // The mated position is found with one ply left to search
+    for depth in 1..=5 {
+        let result = engine.search(&limits(depth));
+        if depth >= 4 {
+            assert_eq!(result.score, MATE_SCORE - 3);
+        }
+    }
+
+    // After the first move the mate is one move closer
+    engine
+        .set_position("kbK5/pp6/1P6/8/8/8/8/R7 w - - 0 1", &["a1a6", "b7a6"])
+        .unwrap();
+    assert_eq!(engine.search(&limits(2)).score, MATE_SCORE - 1);
+}
+
+#[test]
+fn test_hash_option() {
+    let mut engine = Engine::new();
+    assert!(engine.set_option("Hash", "1").is_ok());
+    assert!(engine.set_option("hash", "64").is_ok());
+    assert!(engine.set_option("Hash", "0").is_err());
+    assert!(engine.set_option("Hash", "lots").is_err());
+
+    // Even a tiny table gives the same result
+    engine.set_option("Hash", "1").unwrap();
+    let result = engine.search(&limits(3));
+    let mut fresh = Engine::new();
+    assert_eq!(fresh.search(&limits(3)).best_move, result.best_move);
+
+    let mut output = Vec::new();
+    let commands = "uci\nsetoption name Hash value 32\nsetoption name Hash value 0\n";
+    uci::run(Cursor::new(commands.to_string()), &mut output).unwrap();
+    let output = String::from_utf8(output).unwrap();
+    assert!(output.contains("option name Hash type spin default 16 min 1 max 4096\n"));
+    assert!(output.ends_with("uciok\ninfo string invalid Hash size 0\n"));
+}
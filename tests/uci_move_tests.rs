@@ -0,0 +1,57 @@
+use cherris::*;
+
+#[test]
+fn test_uci_round_trip() {
+    let game =
+        Game::from_fen("r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1")
+            .unwrap();
+    for chess_move in game.legal_moves() {
+        assert_eq!(Move::from_uci(&game, &chess_move.to_uci()), Ok(chess_move));
+    }
+}
+
+#[test]
+fn test_uci_special_moves() {
+    let game = Game::from_fen("r3k3/1P6/8/3pP3/8/8/8/R3K2R w KQq d6 0 1").unwrap();
+
+    let castle = Move::from_uci(&game, "e1g1").unwrap();
+    assert!(castle.is_castle());
+    let en_passant = Move::from_uci(&game, "e5d6").unwrap();
+    assert!(en_passant.is_en_passant());
+    let promotion = Move::from_uci(&game, "b7a8n").unwrap();
+    assert_eq!(promotion.promotion(), Some(PieceType::Knight));
+    assert!(promotion.is_capture());
+    assert_eq!(promotion.to_uci(), "b7a8n");
+}
+
+#[test]
+fn test_uci_errors() {
+    let game = Game::init();
+    assert_eq!(
+        Move::from_uci(&game, "e2e5"),
+        Err(UciMoveError::IllegalMove(String::from("e2e5")))
+    );
+    for uci in ["", "e2", "e2e4e", "i2i4", "e2e9", "0000", "e2e4Q"] {
+        assert_eq!(
+            Move::from_uci(&game, uci),
+            Err(UciMoveError::Syntax(String::from(uci))),
+        );
+    }
+
+    // promotions need the piece letter
+    let game = Game::from_fen("4k3/1P6/8/8/8/8/8/4K3 w - - 0 1").unwrap();
+    assert!(matches!(
+        Move::from_uci(&game, "b7b8"),
+        Err(UciMoveError::IllegalMove(_))
+    ));
+    assert!(Move::from_uci(&game, "b7b8q").is_ok());
+}
+
+#[test]
+fn test_parse_square() {
+    assert_eq!(position_helper::parse_square("a8"), Some(0x00));
+    assert_eq!(position_helper::parse_square("h1"), Some(0x77));
+    assert_eq!(position_helper::parse_square("e9"), None);
+    assert_eq!(position_helper::parse_square("e"), None);
+    assert_eq!(position_helper::parse_square("e44"), None);
+}
@@ -0,0 +1,115 @@
+use std::io::Cursor;
+
+use cherris::cherris_engine::{uci, Engine, SearchLimits};
+use cherris::*;
+
+fn run(commands: &str) -> Vec<String> {
+    let mut output = Vec::new();
+    uci::run(Cursor::new(commands.to_string()), &mut output).unwrap();
+    String::from_utf8(output)
+        .unwrap()
+        .lines()
+        .map(String::from)
+        .collect()
+}
+
+#[test]
+fn test_uci_handshake() {
+    let output = run("uci\nisready\nquit\n");
+    assert!(output[0].starts_with("id name cherris"));
+    assert_eq!(output[output.len() - 2], "uciok");
+    assert_eq!(output[output.len() - 1], "readyok");
+}
+
+#[test]
+fn test_uci_go_plays_a_legal_move() {
+    let output = run("position startpos moves e2e4 e7e5 g1f3\ngo depth 1\nquit\n");
+    let best_move = output.last().unwrap().strip_prefix("bestmove ").unwrap();
+
+    let mut game = Game::init();
+    for uci in ["e2e4", "e7e5", "g1f3"] {
+        let chess_move = Move::from_uci(&game, uci).unwrap();
+        assert!(game.make_move(chess_move));
+    }
+    assert!(!game.white_turn());
+    assert!(Move::from_uci(&game, best_move).is_ok());
+}
+
+#[test]
+fn test_uci_go_without_moves() {
+    let output = run("position fen 7k/5Q2/6K1/8/8/8/8/8 b - - 0 1\ngo movetime 100\n");
+    assert_eq!(output, vec!["bestmove 0000"]);
+}
+
+#[test]
+fn test_uci_infinite_search_waits_for_stop() {
+    // isready is answered while the engine thinks
+    let output = run("position startpos\ngo infinite\nisready\nstop\nisready\n");
+    let ready = output.iter().position(|line| line == "readyok").unwrap();
+    let best_move = output
+        .iter()
+        .position(|line| line.starts_with("bestmove "))
+        .unwrap();
+    assert!(ready < best_move);
+    assert_eq!(output.iter().filter(|line| *line == "readyok").count(), 2);
+
+    // the end of the input stops the search like a stop command would
+    let output = run("position startpos\ngo infinite\n");
+    assert!(output.last().unwrap().starts_with("bestmove "));
+}
+
+#[test]
+fn test_uci_quit_waits_for_the_search() {
+    // Mate in two, only found at depth 4
+    let output = run("position fen kbK5/pp6/1P6/8/8/8/8/R7 w - - 0 1\ngo depth 4\nquit\n");
+    assert!(output[output.len() - 2].starts_with("info depth 4 score mate 2 "));
+    assert_eq!(output[output.len() - 1], "bestmove a1a6");
+
+    // The end of the input waits for the search like quit does
+    let output = run("position fen kbK5/pp6/1P6/8/8/8/8/R7 w - - 0 1\ngo depth 4\n");
+    assert!(output[output.len() - 2].starts_with("info depth 4 score mate 2 "));
+    assert_eq!(output[output.len() - 1], "bestmove a1a6");
+
+    // Commands sent during a search are handled once it is over
+    let output = run("position startpos\ngo depth 2\nposition startpos moves e2e4\ngo depth 1\n");
+    let best_moves: Vec<&String> = output
+        .iter()
+        .filter(|line| line.starts_with("bestmove "))
+        .collect();
+    assert_eq!(best_moves.len(), 2);
+    assert!(output[output.len() - 2].starts_with("info depth 1 "));
+}
+
+#[test]
+fn test_uci_errors_are_reported() {
+    let output = run(
+        "position startpos moves e2e5\nposition fen 8/8\nsetoption name Style value Risky\nfoo\n",
+    );
+    assert_eq!(output.len(), 4);
+    assert!(output.iter().all(|line| line.starts_with("info string")));
+    assert_eq!(output[2], "info string unknown option Style");
+}
+
+#[test]
+fn test_engine_set_position() {
+    let mut engine = Engine::new();
+    engine
+        .set_position(STARTING_FEN, &["e2e4", "c7c5"])
+        .unwrap();
+    assert_eq!(
+        engine.game().to_fen(),
+        "rnbqkbnr/pp1ppppp/8/2p5/4P3/8/PPPP1PPP/RNBQKBNR w KQkq c6 0 2"
+    );
+    // a bad move keeps the previous position
+    assert!(engine
+        .set_position(STARTING_FEN, &["e2e4", "e2e4"])
+        .is_err());
+    assert_eq!(engine.game().moves_done().len(), 2);
+
+    let limits = SearchLimits {
+        depth: Some(1),
+        ..SearchLimits::default()
+    };
+    let best_move = engine.search(&limits).best_move.unwrap();
+    assert!(engine.game().legal_moves().contains(&best_move));
+}
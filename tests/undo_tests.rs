@@ -0,0 +1,105 @@
+mod common;
+
+use cherris::*;
+use common::square;
+
+// Plays every legal move of the position and checks that undoing it gives back the same FEN
+fn assert_all_moves_undo(fen: &str) {
+    let mut game = Game::from_fen(fen).unwrap();
+    for chess_move in game.legal_moves() {
+        assert!(game.make_move(chess_move));
+        assert_eq!(game.undo_move(), Some(chess_move));
+        assert_eq!(game.to_fen(), fen, "after undoing {}", chess_move);
+    }
+}
+
+#[test]
+fn test_undo_restores_capture() {
+    let mut game =
+        Game::from_fen("rnbqkbnr/ppp1pppp/8/3p4/4P3/8/PPPP1PPP/RNBQKBNR w KQkq d6 0 2").unwrap();
+    let fen = game.to_fen();
+    let pieces = game.board().pieces.clone();
+
+    assert!(game.play_move(square("e4"), square("d5")));
+    assert_eq!(game.board().pieces.len(), 31);
+    assert_eq!(
+        game.undo_move().map(|m| m.final_position()),
+        Some(square("d5"))
+    );
+    assert_eq!(game.to_fen(), fen);
+    assert_eq!(game.board().pieces, pieces);
+    assert!(game.moves_done().is_empty());
+}
+
+#[test]
+fn test_undo_restores_special_moves() {
+    // castling both ways
+    assert_all_moves_undo("r3k2r/8/8/8/8/8/8/R3K2R w KQkq - 3 10");
+    assert_all_moves_undo("r3k2r/8/8/8/8/8/8/R3K2R b KQkq - 3 10");
+    // en passant
+    assert_all_moves_undo("rnbqkbnr/pppp1ppp/8/8/3Pp3/8/PPP1PPPP/RNBQKBNR b KQkq d3 0 3");
+    // promotions, with and without capture
+    assert_all_moves_undo("1r2k3/P7/8/8/8/8/7p/4K1N1 w - - 0 40");
+    assert_all_moves_undo("1r2k3/P7/8/8/8/8/7p/4K1N1 b - - 0 40");
+    assert_all_moves_undo("r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1");
+}
+
+#[test]
+fn test_undo_whole_game() {
+    let mut game = Game::init();
+    let moves = [
+        ("e2", "e4"),
+        ("d7", "d5"),
+        ("e4", "e5"),
+        ("f7", "f5"),
+        ("e5", "f6"),
+        ("e8", "f7"),
+        ("f1", "c4"),
+        ("d5", "c4"),
+        ("g1", "f3"),
+        ("c8", "e6"),
+        ("e1", "g1"),
+    ];
+    let mut fens = vec![game.to_fen()];
+    for (from, to) in moves {
+        assert!(game.play_move(square(from), square(to)));
+        fens.push(game.to_fen());
+    }
+
+    while game.undo_move().is_some() {
+        fens.pop();
+        assert_eq!(&game.to_fen(), fens.last().unwrap());
+    }
+    assert_eq!(game.to_fen(), STARTING_FEN);
+    assert_eq!(game.repetition_count(), 1);
+}
+
+#[test]
+fn test_undo_reopens_finished_game() {
+    let mut game = Game::init();
+    for (from, to) in [("f2", "f3"), ("e7", "e5"), ("g2", "g4"), ("d8", "h4")] {
+        assert!(game.play_move(square(from), square(to)));
+    }
+    assert!(game.game_done());
+
+    game.undo_move();
+    assert!(!game.game_done());
+    assert_eq!(game.result(), None);
+    assert!(game.play_move(square("d8"), square("e7")));
+}
+
+#[test]
+fn test_board_make_unmake() {
+    let mut board = Board::from_fen("4k3/8/8/8/8/8/8/R3K3").unwrap();
+    board.update_hashmap();
+    let original = board.clone();
+
+    let chess_move = Move::new(square("e1"), square("c1"), Move::QUEEN_CASTLE);
+    let taken = board.make_move(chess_move);
+    assert_eq!(taken, 0);
+    assert_eq!(board.to_fen(), "4k3/8/8/8/8/8/8/2KR4");
+
+    board.unmake_move(chess_move, taken);
+    assert_eq!(board.state, original.state);
+    assert_eq!(board.pieces, original.pieces);
+}
@@ -0,0 +1,130 @@
+use std::io::Cursor;
+
+use cherris::cherris_engine::{self, xboard};
+use cherris::*;
+
+fn run(commands: &str) -> Vec<String> {
+    let mut output = Vec::new();
+    xboard::run(Cursor::new(commands.to_string()), &mut output).unwrap();
+    String::from_utf8(output)
+        .unwrap()
+        .lines()
+        .map(String::from)
+        .collect()
+}
+
+fn engine_moves(output: &[String]) -> Vec<&str> {
+    output
+        .iter()
+        .filter_map(|line| line.strip_prefix("move "))
+        .collect()
+}
+
+#[test]
+fn test_xboard_handshake() {
+    let output = run("xboard\nprotover 2\nping 7\nquit\n");
+    assert!(output[0].starts_with("feature "));
+    assert!(output[0].contains("usermove=1"));
+    assert!(output[0].ends_with("done=1"));
+    assert_eq!(output[1], "pong 7");
+}
+
+#[test]
+fn test_xboard_engine_answers_moves() {
+    let output = run("xboard\nnew\nsd 1\nusermove e2e4\nusermove g1f3\nquit\n");
+    let moves = engine_moves(&output);
+    assert_eq!(moves.len(), 2);
+
+    // The answers are legal in the game the GUI sees
+    let mut game = Game::init();
+    for uci in ["e2e4", moves[0], "g1f3", moves[1]] {
+        let chess_move = Move::from_uci(&game, uci).unwrap();
+        assert!(game.make_move(chess_move));
+    }
+}
+
+#[test]
+fn test_xboard_force_and_go() {
+    // In force mode the moves of both sides are only recorded
+    let output = run("xboard\nnew\nforce\nusermove e2e4\nusermove e7e5\nping 1\nquit\n");
+    assert_eq!(output, vec!["pong 1"]);
+
+    // go makes the engine play the side to move, here white
+    let output = run("xboard\nnew\nforce\nusermove e2e4\nusermove e7e5\ngo\nquit\n");
+    let moves = engine_moves(&output);
+    assert_eq!(moves.len(), 1);
+    let mut game = Game::init();
+    for uci in ["e2e4", "e7e5"] {
+        let chess_move = Move::from_uci(&game, uci).unwrap();
+        assert!(game.make_move(chess_move));
+    }
+    assert!(Move::from_uci(&game, moves[0]).is_ok());
+
+    // undo takes back the last move
+    let output = run("xboard\nnew\nforce\nusermove e2e4\nundo\nusermove e2e4\nping 2\nquit\n");
+    assert_eq!(output, vec!["pong 2"]);
+}
+
+#[test]
+fn test_xboard_illegal_moves_and_results() {
+    let output = run("xboard\nnew\nusermove e2e5\nfoo\nquit\n");
+    assert_eq!(
+        output,
+        vec!["Illegal move: e2e5", "Error (unknown command): foo"]
+    );
+
+    // The engine reports the end of the game after the move of the GUI
+    let output = run(
+        "xboard\nnew\nforce\nusermove f2f3\nusermove e7e5\nusermove g2g4\nusermove d8h4\nquit\n",
+    );
+    assert_eq!(output, vec!["0-1 {Black mates}"]);
+
+    let output = run("xboard\nsetboard 8/8\nquit\n");
+    assert_eq!(output, vec!["tellusererror Illegal position"]);
+}
+
+#[test]
+fn test_xboard_setboard_and_levels() {
+    // Black to move has a single legal move
+    let output = run(
+        "xboard\nnew\nforce\nlevel 40 5 0\nst 1\ntime 30000\notim 30000\n\
+         setboard 7k/8/8/6Q1/8/8/8/K7 b - - 0 1\ngo\nquit\n",
+    );
+    assert_eq!(engine_moves(&output), vec!["h8h7"]);
+}
+
+#[test]
+fn test_xboard_commands_during_a_search() {
+    // quit waits for the move, here a mate in two only found at depth 4
+    let output =
+        run("xboard\nnew\nforce\nsd 4\nsetboard kbK5/pp6/1P6/8/8/8/8/R7 w - - 0 1\ngo\nquit\n");
+    assert_eq!(engine_moves(&output), vec!["a1a6"]);
+
+    // So does the end of the input
+    let output = run("xboard\nnew\nforce\nsd 4\nsetboard kbK5/pp6/1P6/8/8/8/8/R7 w - - 0 1\ngo\n");
+    assert_eq!(engine_moves(&output), vec!["a1a6"]);
+    let output = run("xboard\nprotover 2\nnew\nsd 3\nusermove e2e4\n");
+    assert_eq!(engine_moves(&output).len(), 1);
+
+    // A new game takes the move away, the commands after it are handled once the search is over
+    let output = run("xboard\nnew\nsd 4\nusermove e2e4\nnew\nping 1\nquit\n");
+    assert_eq!(output, vec!["pong 1"]);
+
+    // ? has the move played now, before the pong
+    let output = run("xboard\nnew\nsd 10\nusermove e2e4\n?\nping 2\nquit\n");
+    assert_eq!(output.len(), 2);
+    assert!(output[0].starts_with("move "));
+    assert_eq!(output[1], "pong 2");
+}
+
+#[test]
+fn test_engine_protocol_dispatch() {
+    let run_engine = |commands: &str| {
+        let mut output = Vec::new();
+        cherris_engine::run(Cursor::new(commands.to_string()), &mut output).unwrap();
+        String::from_utf8(output).unwrap()
+    };
+    assert!(run_engine("\nxboard\nprotover 2\n").starts_with("feature "));
+    assert!(run_engine("uci\n").ends_with("uciok\n"));
+    assert_eq!(run_engine(""), "");
+}
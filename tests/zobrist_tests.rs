@@ -0,0 +1,88 @@
+mod common;
+
+use cherris::*;
+use common::square;
+
+fn play(game: &mut Game, moves: &[(&str, &str)]) {
+    for &(from, to) in moves {
+        assert!(game.play_move(square(from), square(to)));
+    }
+}
+
+#[test]
+fn test_zobrist_matches_fen() {
+    let mut game = Game::init();
+    assert_eq!(
+        game.zobrist_key(),
+        Game::from_fen(STARTING_FEN).unwrap().zobrist_key()
+    );
+
+    play(&mut game, &[("e2", "e4"), ("c7", "c5"), ("g1", "f3")]);
+    let fen = game.to_fen();
+    assert_eq!(
+        game.zobrist_key(),
+        Game::from_fen(&fen).unwrap().zobrist_key()
+    );
+    assert_eq!(
+        game.board().zobrist_key(),
+        Board::from_fen(&fen).unwrap().zobrist_key()
+    );
+}
+
+#[test]
+fn test_zobrist_transpositions() {
+    let mut first = Game::init();
+    play(
+        &mut first,
+        &[("g1", "f3"), ("g8", "f6"), ("b1", "c3"), ("b8", "c6")],
+    );
+    let mut second = Game::init();
+    play(
+        &mut second,
+        &[("b1", "c3"), ("b8", "c6"), ("g1", "f3"), ("g8", "f6")],
+    );
+    assert_eq!(first.zobrist_key(), second.zobrist_key());
+
+    // the same pieces with the other side to move
+    let mut third = Game::init();
+    play(&mut third, &[("g1", "f3"), ("g8", "f6"), ("b1", "c3")]);
+    let mut fourth = Game::init();
+    play(&mut fourth, &[("b1", "c3"), ("g8", "f6"), ("g1", "f3")]);
+    assert_eq!(third.zobrist_key(), fourth.zobrist_key());
+    assert_ne!(first.zobrist_key(), third.zobrist_key());
+}
+
+#[test]
+fn test_zobrist_state_fields() {
+    let key = |fen: &str| Game::from_fen(fen).unwrap().zobrist_key();
+    let base = key("r3k2r/8/8/8/8/8/8/R3K2R w KQkq - 0 1");
+    assert_ne!(base, key("r3k2r/8/8/8/8/8/8/R3K2R b KQkq - 0 1"));
+    assert_ne!(base, key("r3k2r/8/8/8/8/8/8/R3K2R w Kkq - 0 1"));
+    assert_ne!(base, key("r3k2r/8/8/8/8/8/8/R3K2R w - - 0 1"));
+    // the clocks are not part of the position
+    assert_eq!(base, key("r3k2r/8/8/8/8/8/8/R3K2R w KQkq - 20 31"));
+
+    // the en passant file only counts when a pawn can take
+    assert_eq!(
+        key("4k3/8/8/8/4P3/8/8/4K3 b - e3 0 1"),
+        key("4k3/8/8/8/4P3/8/8/4K3 b - - 0 1")
+    );
+    assert_ne!(
+        key("4k3/8/8/8/3pP3/8/8/4K3 b - e3 0 1"),
+        key("4k3/8/8/8/3pP3/8/8/4K3 b - - 0 1")
+    );
+}
+
+#[test]
+fn test_zobrist_restored_by_undo() {
+    let mut game =
+        Game::from_fen("r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1")
+            .unwrap();
+    let key = game.zobrist_key();
+    for chess_move in game.legal_moves() {
+        assert!(game.make_move(chess_move));
+        assert_ne!(game.zobrist_key(), key);
+        game.undo_move();
+        assert_eq!(game.zobrist_key(), key, "after undoing {}", chess_move);
+    }
+}